@@ -0,0 +1,125 @@
+use crate::cli::{App, Command};
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Write a commented default config to the config file
+    Init {
+        /// Path to config [default: $XDG_CONFIG_HOME/hyprswitch/config.ron]
+        #[arg(long, short = 'w')]
+        config_file: Option<PathBuf>,
+
+        /// Overwrite the config file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check the config file for errors without generating binds
+    Check {
+        /// Path to config [default: $XDG_CONFIG_HOME/hyprswitch/config.ron]
+        #[arg(long, short = 'w')]
+        config_file: Option<PathBuf>,
+    },
+
+    /// Print the default config
+    PrintDefault,
+
+    /// Convert a bind line using the CLI (`hyprswitch gui` / `hyprswitch simple`) into a bind for the config file
+    Migrate {
+        /// The bind line from the Hyprland config, e.g. "bind = alt, tab, exec, hyprswitch gui --mod-key alt_l --key tab --close mod-key-release && hyprswitch dispatch"
+        line: String,
+    },
+}
+
+/// converts a bind line like `bind = alt, tab, exec, hyprswitch gui ...` into a bind of the config
+pub fn migrate(line: &str) -> anyhow::Result<String> {
+    let (keyword, value) = line
+        .split_once('=')
+        .context("Expected a bind line like `bind = MODS, key, exec, hyprswitch ...`")?;
+    if !keyword.trim().starts_with("bind") {
+        anyhow::bail!("Expected a bind, got {:?}", keyword.trim());
+    }
+    let mut parts = value.splitn(4, ',');
+    let (Some(modifier), Some(key), Some(dispatcher), Some(command)) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        anyhow::bail!("Expected a bind line like `bind = MODS, key, exec, hyprswitch ...`");
+    };
+    if dispatcher.trim() != "exec" {
+        anyhow::bail!("Expected an exec bind, got {:?}", dispatcher.trim());
+    }
+
+    // only the first command opens the GUI (followed by e.g. `&& hyprswitch dispatch`)
+    let command = command.split(" && ").next().unwrap_or_default();
+    let app = App::try_parse_from(command_args(command))
+        .with_context(|| format!("Failed to parse hyprswitch command {:?}", command.trim()))?;
+    match app.command {
+        Command::Gui {
+            submap_conf,
+            reverse_key,
+            gui_conf,
+            simple_config,
+        } => hyprswitch::config::migrate_gui(
+            modifier,
+            key.trim(),
+            simple_config.into(),
+            gui_conf.into(),
+            submap_conf.into_submap_conf(reverse_key),
+        ),
+        Command::Simple {
+            dispatch_config,
+            simple_conf,
+        } => hyprswitch::config::migrate_simple(
+            modifier,
+            key.trim(),
+            simple_conf.into(),
+            dispatch_config.into(),
+        ),
+        command => anyhow::bail!(
+            "Only binds using `hyprswitch gui` or `hyprswitch simple` can be migrated, got {command:?}"
+        ),
+    }
+}
+
+/// arguments of a command in a bind (without env variables set before the command)
+pub(super) fn command_args(command: &str) -> Vec<String> {
+    shell_split(command)
+        .into_iter()
+        .skip_while(|arg| arg.contains('=') && !arg.starts_with('-'))
+        .collect()
+}
+
+/// splits a command like `sh -c` would (only supports simple quoting like the one used by the generator)
+pub(super) fn shell_split(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    arg.push(c);
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    current.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    args
+}
@@ -14,4 +14,7 @@ pub enum DebugCommand {
 
     /// List all desktop files
     DesktopFiles,
+
+    /// List the launch history of the launcher sorted by frecency
+    History,
 }
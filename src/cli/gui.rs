@@ -9,8 +9,8 @@ use std::str::FromStr;
 
 #[derive(Args, Debug, Clone)]
 pub struct SubmapInfo {
-    /// The name of the submap to activate (generated by hyprswitch generate) [conflicts with --mod_key, --key, --close]
-    #[arg(long, conflicts_with_all = ["mod_key", "key", "close"])]
+    /// The name of the submap to activate (generated by hyprswitch generate)
+    #[arg(long)]
     pub submap: String,
 }
 
@@ -53,10 +53,23 @@ pub struct GuiConf {
     )]
     pub show_workspaces_on_all_monitors: bool,
 
-    /// Show the application launcher in the GUI
-    #[arg(long, default_value = "false", action = clap::ArgAction::Set, default_missing_value = "true", num_args=0..=1
+    /// Show the application launcher in the GUI [default: SHOW_LAUNCHER / launcher.enable of the daemon, never when closing with mod-key-release]
+    #[arg(long, action = clap::ArgAction::Set, default_missing_value = "true", num_args=0..=1
     )]
-    pub show_launcher: bool,
+    pub show_launcher: Option<bool>,
+
+    /// Maximum number of items in the launcher [default: LAUNCHER_MAX_ITEMS / launcher.items of the daemon]
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=10))]
+    pub launcher_max_items: Option<u8>,
+
+    /// Show the command of the applications in the launcher [default: SHOW_LAUNCHER_EXECS / launcher.show_execs of the daemon]
+    #[arg(long, action = clap::ArgAction::Set, default_missing_value = "true", num_args=0..=1
+    )]
+    pub show_launcher_execs: Option<bool>,
+
+    /// Terminal for applications launched from the launcher that run in a terminal, e.g., alacritty [default: DEFAULT_TERMINAL / launcher.default_terminal of the daemon]
+    #[arg(long)]
+    pub default_terminal: Option<String>,
 }
 
 impl From<GuiConf> for GuiConfig {
@@ -67,12 +80,14 @@ impl From<GuiConf> for GuiConfig {
             monitors: opts.monitors.map(|m| m.0),
             show_workspaces_on_all_monitors: opts.show_workspaces_on_all_monitors,
             show_launcher: opts.show_launcher,
+            launcher_max_items: opts.launcher_max_items.map(usize::from),
+            show_launcher_execs: opts.show_launcher_execs,
+            default_terminal: opts.default_terminal,
         }
     }
 }
 
 impl SubmapInfo {
-    #[allow(dead_code)]
     pub fn into_submap_info(self, reverse_key: InputReverseKey) -> SubmapConfig {
         SubmapConfig::Name {
             name: self.submap,
@@ -132,3 +147,91 @@ impl FromStr for Monitors {
         Ok(Self(vec))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{App, Command};
+    use clap::Parser;
+
+    /// launcher settings of a GUI opened with the arguments by a daemon with the init_config
+    fn launcher_config(
+        args: &[&str],
+        init_config: &crate::InitConfig,
+    ) -> hyprswitch::LauncherConfig {
+        let app = App::try_parse_from(["hyprswitch", "gui"].iter().chain(args))
+            .expect("Failed to parse gui");
+        let Command::Gui {
+            submap_conf,
+            reverse_key,
+            gui_conf,
+            ..
+        } = app.command
+        else {
+            panic!("Expected gui");
+        };
+        hyprswitch::LauncherConfig::new(
+            &gui_conf.into(),
+            init_config,
+            &submap_conf.into_submap_conf(reverse_key),
+        )
+    }
+
+    #[test]
+    fn gui_launcher_options_override_daemon() {
+        let init_config = crate::InitConfig {
+            show_launcher: true,
+            launcher_max_items: 5,
+            default_terminal: Some("alacritty".to_string()),
+            ..Default::default()
+        };
+        let launcher = launcher_config(&["--mod-key", "alt", "--key", "tab"], &init_config);
+        assert!(launcher.show);
+        assert_eq!(launcher.max_items, 5);
+        assert_eq!(launcher.default_terminal.as_deref(), Some("alacritty"));
+
+        let launcher = launcher_config(
+            &[
+                "--mod-key",
+                "alt",
+                "--key",
+                "tab",
+                "--launcher-max-items=9",
+                "--show-launcher-execs=false",
+                "--default-terminal=kitty",
+            ],
+            &init_config,
+        );
+        assert_eq!(launcher.max_items, 9);
+        assert!(!launcher.show_execs);
+        assert_eq!(launcher.default_terminal.as_deref(), Some("kitty"));
+
+        let launcher = launcher_config(
+            &["--mod-key", "alt", "--key", "tab", "--show-launcher=false"],
+            &init_config,
+        );
+        assert!(!launcher.show);
+    }
+
+    #[test]
+    fn gui_launcher_not_shown_on_mod_key_release() {
+        let init_config = crate::InitConfig {
+            show_launcher: true,
+            ..Default::default()
+        };
+        let args = [
+            "--mod-key",
+            "alt",
+            "--key",
+            "tab",
+            "--close",
+            "mod-key-release",
+        ];
+        assert!(!launcher_config(&args, &init_config).show);
+        // unless it is requested explicitly
+        let args = [&args[..], &["--show-launcher"]].concat();
+        assert!(launcher_config(&args, &init_config).show);
+        // the launcher isn't shown if the daemon and the GUI don't enable it
+        let init_config = crate::InitConfig::default();
+        assert!(!launcher_config(&["--mod-key", "alt", "--key", "tab"], &init_config).show);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::InitConfig;
 use clap::Args;
+use hyprswitch::envs::get_envs;
+use hyprswitch::InitOverrides;
 use std::path::PathBuf;
 
 #[derive(Args, Debug, Clone)]
@@ -8,27 +10,95 @@ pub struct InitOpts {
     #[arg(long)]
     pub custom_css: Option<PathBuf>,
 
-    /// Show the windows title instead of its class in Overview (fallback to class if title is empty)
-    #[arg(long, default_value = "true", action = clap::ArgAction::Set, default_missing_value = "true", num_args=0..=1
+    /// Show the windows title instead of its class in Overview (fallback to class if title is empty) [default: true]
+    #[arg(long, action = clap::ArgAction::Set, default_missing_value = "true", num_args=0..=1
     )]
-    pub show_title: bool,
+    pub show_title: Option<bool>,
 
-    /// Limit amount of workspaces in one row (overflows to next row)
-    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u8).range(1..))]
-    pub workspaces_per_row: u8,
+    /// Limit amount of workspaces in one row (overflows to next row) [default: 5]
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..))]
+    pub workspaces_per_row: Option<u8>,
 
-    /// The size factor (float) for the GUI (original_size / 30 * size_factor)
-    #[arg(long, default_value = "6")]
-    pub size_factor: f64,
+    /// The size factor (float) for the GUI (original_size / 30 * size_factor) [default: 6]
+    #[arg(long)]
+    pub size_factor: Option<f64>,
+
+    /// Load the settings from the config file (config.ron) and reload them when it changes (options and env variables take precedence)
+    #[cfg(feature = "config")]
+    #[arg(long)]
+    pub config: bool,
+
+    /// Register the binds from the config file when starting (and remove them on exit), so they don't need to be added to the Hyprland config
+    #[cfg(feature = "config")]
+    #[arg(long, requires = "config")]
+    pub register_binds: bool,
 }
 
-impl From<InitOpts> for InitConfig {
-    fn from(opts: InitOpts) -> Self {
-        Self {
+/// settings are taken from the CLI, env variables, config file and defaults (in this order)
+impl TryFrom<InitOpts> for InitConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(opts: InitOpts) -> anyhow::Result<Self> {
+        let overrides = InitOverrides {
             custom_css: opts.custom_css,
             show_title: opts.show_title,
             workspaces_per_row: opts.workspaces_per_row,
             size_factor: opts.size_factor,
+            ..Default::default()
+        }
+        .or(get_envs().overrides.clone());
+        let init_config = Self {
+            #[cfg(feature = "config")]
+            config_file: opts.config.then(hyprswitch::config::get_path).flatten(),
+            #[cfg(feature = "config")]
+            register_binds: opts.register_binds,
+            overrides,
+            ..Default::default()
         }
+        .apply_overrides();
+        init_config.validate()?;
+        Ok(init_config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{App, Command};
+    use clap::Parser;
+
+    #[test]
+    fn init_options_take_precedence() {
+        let app = App::try_parse_from([
+            "hyprswitch",
+            "init",
+            "--size-factor",
+            "3.5",
+            "--show-title=false",
+        ])
+        .expect("Failed to parse init");
+        let Command::Init { init_opts } = app.command else {
+            panic!("Expected init");
+        };
+        let init_config = crate::InitConfig::try_from(init_opts).expect("Failed to read settings");
+        assert_eq!(init_config.size_factor, 3.5);
+        assert!(!init_config.show_title);
+        assert_eq!(init_config.workspaces_per_row, 5);
+        assert_eq!(init_config.overrides.size_factor, Some(3.5));
+        assert_eq!(init_config.overrides.workspaces_per_row, None);
+    }
+
+    #[test]
+    fn init_invalid_settings_are_errors() {
+        let app = App::try_parse_from(["hyprswitch", "init", "--size-factor=-1"])
+            .expect("Failed to parse init");
+        let Command::Init { init_opts } = app.command else {
+            panic!("Expected init");
+        };
+        let error =
+            crate::InitConfig::try_from(init_opts).expect_err("Invalid settings were accepted");
+        assert_eq!(
+            error.to_string(),
+            "Found 1 invalid settings:\n- size_factor must be greater than 0 (is -1)"
+        );
     }
 }
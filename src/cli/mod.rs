@@ -1,14 +1,20 @@
+#[cfg(feature = "config")]
+mod config;
 mod debug;
 mod dispatch;
 mod gui;
 mod init;
 mod shared;
 mod simple;
+#[cfg(all(test, feature = "config"))]
+mod tests;
 
 use std::fmt::Debug;
 
 use clap::{Args, Parser, Subcommand};
 
+#[cfg(feature = "config")]
+pub use config::{migrate, ConfigCommand};
 pub use debug::DebugCommand;
 
 #[derive(Parser, Debug, Clone)]
@@ -54,6 +60,12 @@ pub enum Command {
         #[arg(long)]
         exe: Option<std::path::PathBuf>,
     },
+    #[cfg(feature = "config")]
+    /// Create, check and migrate the config file
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommand,
+    },
     /// Initialize and start the Daemon
     Init {
         #[clap(flatten)]
@@ -83,6 +95,24 @@ pub enum Command {
         #[clap(flatten)]
         simple_config: simple::SimpleConf,
     },
+    /// Open the GUI with a submap generated by `hyprswitch generate` (used in the generated binds)
+    #[clap(hide = true)]
+    GuiNoSubmap {
+        #[clap(flatten)]
+        submap_info: gui::SubmapInfo,
+
+        /// The key used for reverse switching. Format: reverse-key=mod=<MODIFIER> or reverse-key=key=<KEY> (e.g., --reverse-key=mod=shift, --reverse-key=key=grave)
+        ///
+        /// The submap already contains the binds, this is only used to display the correct keybinding in the GUI
+        #[arg(long, value_parser = clap::value_parser!(shared::InputReverseKey), default_value = "mod=shift")]
+        reverse_key: shared::InputReverseKey,
+
+        #[clap(flatten)]
+        gui_conf: gui::GuiConf,
+
+        #[clap(flatten)]
+        simple_config: simple::SimpleConf,
+    },
     /// Switch without using the GUI / Daemon (switches directly)
     Simple {
         #[clap(flatten)]
@@ -98,6 +128,12 @@ pub enum Command {
         #[arg(long)]
         kill: bool,
     },
+    /// Print the current state of the Daemon as JSON (config, selected item, clients/workspaces/monitors with dispatch offsets, launcher matches)
+    Query,
+    /// Print events of the Daemon as JSON lines (opened, selection changed, closed, launched) until the Daemon exits
+    Subscribe,
+    /// Reload the config file of the Daemon (only if started with `init --config`, changes are also applied automatically)
+    Reload,
     /// Debug command to debug finding icons for the GUI, doesn't interact with the Daemon
     Debug {
         #[clap(subcommand)]
@@ -110,8 +146,18 @@ pub fn check_invalid_inputs(e: &clap::Error) -> bool {
     e.to_string()
         .starts_with("A CLI/GUI that allows switching between windows in Hyprland")
         || e.to_string().starts_with("Initialize and start the Daemon")
+        || e.to_string()
+            .starts_with("Create, check and migrate the config file")
         || e.to_string()
             .starts_with("Switch without using the GUI / Daemon (switches directly)")
+        || e.to_string()
+            .starts_with("Open the GUI with a submap generated by `hyprswitch generate`")
+        || e.to_string()
+            .starts_with("Print the current state of the Daemon as JSON")
+        || e.to_string()
+            .starts_with("Print events of the Daemon as JSON lines")
+        || e.to_string()
+            .starts_with("Reload the config file of the Daemon")
         || e.to_string().starts_with(
             "Debug command to debug finding icons for the GUI, doesn't interact with the Daemon",
         )
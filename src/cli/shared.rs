@@ -37,6 +37,19 @@ impl From<InputReverseKey> for ReverseKey {
     }
 }
 
+/// regex that is checked when parsing, but passed on as string (compiled by the daemon)
+#[derive(Debug, Clone)]
+pub struct InputRegex(pub String);
+
+impl FromStr for InputRegex {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        regex::Regex::new(s).map_err(|e| format!("Invalid regex {s:?}: {e}"))?;
+        Ok(InputRegex(s.to_string()))
+    }
+}
+
 #[derive(Debug, ValueEnum, Clone, Default)]
 pub enum InputSwitchType {
     #[default]
@@ -1,6 +1,7 @@
+use crate::cli::shared;
 use crate::SimpleConfig;
 use clap::Args;
-use crate::cli::shared;
+use hyprswitch::Filter;
 
 #[derive(Args, Debug, Clone)]
 pub struct SimpleConf {
@@ -31,7 +32,39 @@ pub struct SimpleConf {
     #[arg(short = 'm', long)]
     pub filter_current_monitor: bool,
 
-    /// Sort windows by most recently focused
+    /// Only show/switch between windows whose class matches the regex (e.g., --filter-class 'kitty|Alacritty')
+    #[arg(long, value_name = "REGEX", value_parser = clap::value_parser!(shared::InputRegex))]
+    pub filter_class: Vec<shared::InputRegex>,
+
+    /// Only show/switch between windows whose title matches the regex
+    #[arg(long, value_name = "REGEX", value_parser = clap::value_parser!(shared::InputRegex))]
+    pub filter_title: Vec<shared::InputRegex>,
+
+    /// Hide windows whose class matches the regex (can be used multiple times)
+    #[arg(long, value_name = "REGEX", value_parser = clap::value_parser!(shared::InputRegex))]
+    pub exclude_class: Vec<shared::InputRegex>,
+
+    /// Hide windows whose title matches the regex (e.g., --exclude-title 'Picture-in-Picture')
+    #[arg(long, value_name = "REGEX", value_parser = clap::value_parser!(shared::InputRegex))]
+    pub exclude_title: Vec<shared::InputRegex>,
+
+    /// Only show/switch between floating (true) or tiled (false) windows
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub filter_floating: Option<bool>,
+
+    /// Only show/switch between fullscreen (true) or not fullscreen (false) windows
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub filter_fullscreen: Option<bool>,
+
+    /// Only show/switch between pinned (true) or not pinned (false) windows
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub filter_pinned: Option<bool>,
+
+    /// Only show/switch between xwayland (true) or wayland (false) windows
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub filter_xwayland: Option<bool>,
+
+    /// Sort windows (workspaces / monitors when switching by workspace / monitor) by most recently focused
     #[arg(long, default_value = "false", action = clap::ArgAction::Set, default_missing_value = "true", num_args=0..=1
     )]
     pub sort_recent: bool,
@@ -43,6 +76,26 @@ pub struct SimpleConf {
 
 impl From<SimpleConf> for SimpleConfig {
     fn from(opts: SimpleConf) -> Self {
+        let filters = opts
+            .filter_class
+            .into_iter()
+            .map(|r| Filter::Class(r.0))
+            .chain(opts.filter_title.into_iter().map(|r| Filter::Title(r.0)))
+            .chain(
+                opts.exclude_class
+                    .into_iter()
+                    .map(|r| Filter::ExcludeClass(r.0)),
+            )
+            .chain(
+                opts.exclude_title
+                    .into_iter()
+                    .map(|r| Filter::ExcludeTitle(r.0)),
+            )
+            .chain(opts.filter_floating.map(Filter::Floating))
+            .chain(opts.filter_fullscreen.map(Filter::Fullscreen))
+            .chain(opts.filter_pinned.map(Filter::Pinned))
+            .chain(opts.filter_xwayland.map(Filter::Xwayland))
+            .collect();
         Self {
             ignore_monitors: opts.ignore_monitors,
             ignore_workspaces: opts.ignore_workspaces,
@@ -52,6 +105,7 @@ impl From<SimpleConf> for SimpleConfig {
             filter_same_class: opts.filter_same_class,
             include_special_workspaces: opts.include_special_workspaces,
             switch_type: opts.switch_type.into(),
+            filters,
         }
     }
 }
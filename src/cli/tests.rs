@@ -0,0 +1,227 @@
+use crate::cli::config::{command_args, migrate};
+use crate::cli::{App, Command};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// generates all binds for the config and parses every hyprswitch command in them with the [`App`]
+fn parse_generated(config: &str) -> Vec<App> {
+    let config = hyprswitch::config::parse(config).expect("Failed to parse config");
+    let binds =
+        hyprswitch::config::create_binds_and_submaps(Some(PathBuf::from("hyprswitch")), config)
+            .expect("Failed to generate binds");
+
+    let mut apps = Vec::new();
+    for (keyword, value) in &binds {
+        let command = if *keyword == "exec-once" {
+            value.as_str()
+        } else if keyword.starts_with("bind") {
+            match value.split_once("exec, ") {
+                Some((_, command)) => command,
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+        for command in command.trim().split(" && ") {
+            let args = command_args(command);
+            assert_eq!(args.first().map(String::as_str), Some("hyprswitch"));
+            let app = App::try_parse_from(&args)
+                .unwrap_or_else(|e| panic!("Failed to parse generated {command:?}: {e}"));
+            apps.push(app);
+        }
+    }
+    apps
+}
+
+#[test]
+fn generated_daemon_start_parses() {
+    let apps = parse_generated(
+        r#"Config(
+            general: (
+                size_factor: 4.5,
+                custom_css_path: "/home/user/my styles/custom.css",
+                launcher: (enable: true, default_terminal: "alacritty"),
+                gui: (show_title: false, workspaces_per_row: 3),
+            ),
+            binds: []
+        )"#,
+    );
+    assert_eq!(apps.len(), 1);
+    let Command::Init { init_opts } = &apps[0].command else {
+        panic!("Expected init command, got {:?}", apps[0].command);
+    };
+    // settings are loaded from the config file by the daemon
+    assert!(init_opts.config);
+}
+
+#[test]
+fn generated_press_binds_parse() {
+    let apps = parse_generated(
+        r#"Config(
+            general: (),
+            binds: [
+                Press(
+                    open: (modifier: super, key: "tab"),
+                    navigate: (forward: "tab", reverse: Mod(shift)),
+                    other: (switch_type: workspace, sort_by_recent: true),
+                ),
+                Press(
+                    show_launcher: false,
+                    open: (modifier: alt, key: "super_l"),
+                    navigate: (forward: "tab", reverse: Key("grave"), arrow_keys: false),
+                    close: (escape: false, close_on_reopen: false),
+                ),
+            ]
+        )"#,
+    );
+    let submaps = apps
+        .iter()
+        .filter_map(|app| match &app.command {
+            Command::GuiNoSubmap { submap_info, .. } => Some(submap_info.submap.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(submaps.len(), 2);
+    assert!(submaps.iter().all(|s| s.starts_with("hyprswitch-")));
+}
+
+#[test]
+fn generated_hold_binds_parse() {
+    let apps = parse_generated(
+        r#"Config(
+            general: (),
+            binds: [
+                Hold(
+                    open: (modifier: alt),
+                    navigate: (forward: "tab", reverse: Key("grave"), arrow_keys: false),
+                    close: (escape: true),
+                    other: (max_switch_offset: 5, filter_by: [current_monitor, same_class]),
+                ),
+                Hold(
+                    open: (modifier: super),
+                    navigate: (forward: "tab", reverse: Mod(shift), arrow_keys: true),
+                ),
+            ]
+        )"#,
+    );
+    assert!(apps
+        .iter()
+        .any(|app| matches!(app.command, Command::GuiNoSubmap { .. })));
+    assert!(apps
+        .iter()
+        .any(|app| matches!(app.command, Command::Close { kill: false })));
+}
+
+#[test]
+fn generated_simple_binds_parse() {
+    let apps = parse_generated(
+        r#"Config(
+            general: (),
+            binds: [
+                Simple(offset: 1, open: (modifier: ctrl, key: "ctrl")),
+                Simple(
+                    reverse: true,
+                    offset: 2,
+                    open: (modifier: super, key: "tab"),
+                    other: (
+                        include_special_workspaces: true,
+                        filter_by: [class("kitty|Alacritty"), exclude_title("it's PiP"), floating(false)],
+                    ),
+                ),
+            ]
+        )"#,
+    );
+    let simple_confs = apps
+        .iter()
+        .filter_map(|app| match &app.command {
+            Command::Simple { simple_conf, .. } => Some(simple_conf),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(simple_confs.len(), 2);
+    let simple_conf = simple_confs[1];
+    assert_eq!(simple_conf.filter_class[0].0, "kitty|Alacritty");
+    assert_eq!(simple_conf.exclude_title[0].0, "it's PiP");
+    assert_eq!(simple_conf.filter_floating, Some(false));
+}
+
+#[test]
+fn registered_binds_have_no_daemon_start() {
+    let config = hyprswitch::config::parse(
+        r#"Config(
+            general: (),
+            binds: [
+                Press(open: (modifier: super)),
+                Hold(open: (modifier: alt)),
+                Simple(open: (modifier: ctrl, key: "tab")),
+            ]
+        )"#,
+    )
+    .expect("Failed to parse config");
+    let binds = hyprswitch::config::create_binds(Some(PathBuf::from("hyprswitch")), config.binds)
+        .expect("Failed to generate binds");
+    assert!(binds
+        .iter()
+        .all(|(keyword, _)| *keyword == "submap" || keyword.starts_with("bind")));
+    // binds registered after the last submap must not end up inside it
+    assert_eq!(
+        binds
+            .iter()
+            .rev()
+            .find(|(keyword, _)| *keyword == "submap")
+            .map(|(_, value)| value.trim()),
+        Some("reset")
+    );
+}
+
+/// migrates the bind line and parses the result as the only bind of a config
+fn migrate_to_config(line: &str) -> String {
+    let bind = migrate(line).expect("Failed to migrate bind");
+    let config = format!("Config(general: (), binds: [{bind}])");
+    hyprswitch::config::parse(&config).expect("Failed to parse migrated bind");
+    config
+}
+
+#[test]
+fn migrate_press_bind() {
+    let config = migrate_to_config(
+        "bind = super, tab, exec, hyprswitch gui --mod-key super_l --key tab --close default --max-switch-offset 5",
+    );
+    let apps = parse_generated(&config);
+    assert!(apps.iter().any(|app| matches!(
+        &app.command,
+        Command::GuiNoSubmap { gui_conf, .. } if gui_conf.max_switch_offset == 5
+    )));
+}
+
+#[test]
+fn migrate_hold_bind() {
+    let config = migrate_to_config(
+        "bind = alt, tab, exec, hyprswitch gui --mod-key alt_l --key tab --close mod-key-release --reverse-key=mod=shift && hyprswitch dispatch",
+    );
+    assert!(
+        config.starts_with("Config(general: (), binds: [Hold("),
+        "{config}"
+    );
+    assert!(config.contains("reverse: Mod(shift)"), "{config}");
+}
+
+#[test]
+fn migrate_simple_bind() {
+    let config = migrate_to_config(
+        "bind = alt, grave, exec, RUST_LOG=debug hyprswitch simple --offset 2 -r",
+    );
+    assert!(
+        config.starts_with("Config(general: (), binds: [Simple("),
+        "{config}"
+    );
+    assert!(config.contains("offset: 2"), "{config}");
+    assert!(config.contains("reverse: true"), "{config}");
+}
+
+#[test]
+fn migrate_rejects_other_binds() {
+    assert!(migrate("bind = alt, tab, exec, hyprswitch dispatch").is_err());
+    assert!(migrate("bind = alt, tab, workspace, 1").is_err());
+    assert!(migrate("exec-once = hyprswitch init").is_err());
+}
@@ -1,4 +1,4 @@
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::os::unix::net::UnixStream;
 
 use anyhow::Context;
@@ -6,44 +6,120 @@ use tracing::{debug, trace};
 
 use crate::configs::DispatchConfig;
 use crate::{
-    get_socket_path_buff, global, GuiConfig, SimpleConfig, SubmapConfig, Transfer, TransferType,
+    get_socket_path_buff, global, Event, GuiConfig, QueryData, Response, ResponseType,
+    SimpleConfig, SubmapConfig, Transfer, TransferType,
 };
 
-pub fn send_version_check_command() -> anyhow::Result<bool> {
+/// returns the version of the daemon
+pub fn send_version_check_command() -> anyhow::Result<String> {
     let send_struct = Transfer {
         transfer: TransferType::VersionCheck,
         version: env!("CARGO_PKG_VERSION").to_string(),
     };
     debug!("Sending version_check command");
-    let serialized = bincode::serialize(&send_struct)
-        .with_context(|| format!("Failed to serialize transfer {send_struct:?}"))?;
-    send(&serialized)
-        .with_context(|| format!("Failed to send version_check command {serialized:?}"))
+    match send(&send_struct).context("Failed to send version_check command")? {
+        ResponseType::VersionCheck(version) => Ok(version),
+        response => Err(anyhow::anyhow!("Unexpected response {response:?}")),
+    }
 }
 
+/// returns if the gui is open
 pub fn send_open_command() -> anyhow::Result<bool> {
     let send_struct = Transfer {
         transfer: TransferType::Open,
         version: env!("CARGO_PKG_VERSION").to_string(),
     };
     debug!("Sending open command");
-    let serialized = bincode::serialize(&send_struct)
+    match send(&send_struct).context("Failed to send open command")? {
+        ResponseType::Open(open) => Ok(open),
+        response => Err(anyhow::anyhow!("Unexpected response {response:?}")),
+    }
+}
+
+/// returns the current state of the daemon
+pub fn send_query_command() -> anyhow::Result<QueryData> {
+    let send_struct = Transfer {
+        transfer: TransferType::Query,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    debug!("Sending query command");
+    match send(&send_struct).context("Failed to send query command")? {
+        ResponseType::Query(data) => Ok(*data),
+        response => Err(anyhow::anyhow!("Unexpected response {response:?}")),
+    }
+}
+
+/// reloads the config file of the daemon (fails if the gui is open or the daemon wasn't started with `--config`)
+pub fn send_reload_command() -> anyhow::Result<()> {
+    let send_struct = Transfer {
+        transfer: TransferType::Reload,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    debug!("Sending reload command");
+    match send(&send_struct).context("Failed to send reload command")? {
+        ResponseType::Reload => Ok(()),
+        response => Err(anyhow::anyhow!("Unexpected response {response:?}")),
+    }
+}
+
+/// subscribes to the events of the daemon and calls `on_event` for every event until the daemon closes the stream
+///
+/// uses the json protocol as events are sent as json lines
+pub fn send_subscribe_command(mut on_event: impl FnMut(Event)) -> anyhow::Result<()> {
+    let send_struct = Transfer {
+        transfer: TransferType::Subscribe,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    debug!("Sending subscribe command");
+    let mut buffer = serde_json::to_vec(&send_struct)
         .with_context(|| format!("Failed to serialize transfer {send_struct:?}"))?;
-    send(&serialized).with_context(|| format!("Failed to send open command {serialized:?}"))
+    buffer.push(b'\n');
+
+    if *global::DRY.get().expect("DRY not set") {
+        debug!("DRY RUN: Would have sent {buffer:?}");
+        return Ok(());
+    }
+
+    let path_buf = get_socket_path_buff();
+    let path = path_buf.as_path();
+    let mut stream = UnixStream::connect(path)
+        .with_context(|| format!("Failed to connect to socket {path:?}"))?;
+    stream
+        .write_all(&buffer)
+        .with_context(|| format!("Failed to write data {buffer:?} to socket {path:?}"))?;
+
+    let mut lines = BufReader::new(stream).lines();
+    let line = lines
+        .next()
+        .context("Daemon closed the stream without response")?
+        .context("Failed to read data from buffer")?;
+    let response: Response = serde_json::from_str(&line).with_context(|| {
+        format!("Unknown response {line:?} ?? (daemon version probably doesn't match)")
+    })?;
+    trace!("Received response: {response:?}");
+    response.response?;
+
+    for line in lines {
+        let line = line.context("Failed to read data from buffer")?;
+        let event: Event =
+            serde_json::from_str(&line).with_context(|| format!("Unknown event {line:?}"))?;
+        trace!("Received event: {event:?}");
+        on_event(event);
+    }
+    Ok(())
 }
 
 ///
 /// calls [`crate::daemon::handle_fns::switch`]
 ///
-pub fn send_dispatch_command(dispatch_config: DispatchConfig) -> anyhow::Result<bool> {
+pub fn send_dispatch_command(dispatch_config: DispatchConfig) -> anyhow::Result<()> {
     let send_struct = Transfer {
         transfer: TransferType::Dispatch(dispatch_config),
         version: env!("CARGO_PKG_VERSION").to_string(),
     };
     debug!("Sending switch command {send_struct:?}");
-    let serialized = bincode::serialize(&send_struct)
-        .with_context(|| format!("Failed to serialize transfer {send_struct:?}"))?;
-    send(&serialized).with_context(|| format!("Failed to send switch command {serialized:?}"))
+    send(&send_struct).with_context(|| format!("Failed to send switch command {send_struct:?}"))?;
+    Ok(())
 }
 
 ///
@@ -53,29 +129,27 @@ pub fn send_init_command(
     simple_config: SimpleConfig,
     gui_config: GuiConfig,
     submap_config: SubmapConfig,
-) -> anyhow::Result<bool> {
+) -> anyhow::Result<()> {
     let send_struct = Transfer {
         transfer: TransferType::Init(simple_config, gui_config, submap_config),
         version: env!("CARGO_PKG_VERSION").to_string(),
     };
     debug!("Sending init command {send_struct:?}");
-    let serialized = bincode::serialize(&send_struct)
-        .with_context(|| format!("Failed to serialize transfer {send_struct:?}"))?;
-    send(&serialized).with_context(|| format!("Failed to send init command {serialized:?}"))
+    send(&send_struct).with_context(|| format!("Failed to send init command {send_struct:?}"))?;
+    Ok(())
 }
 
 ///
 /// calls [`crate::daemon::handle_fns::close`]
 ///
-pub fn send_close_daemon(kill: bool) -> anyhow::Result<bool> {
+pub fn send_close_daemon(kill: bool) -> anyhow::Result<()> {
     let send_struct = Transfer {
         transfer: TransferType::Close(kill),
         version: env!("CARGO_PKG_VERSION").to_string(),
     };
     debug!("Sending close command {send_struct:?}");
-    let serialized = bincode::serialize(&send_struct)
-        .with_context(|| format!("Failed to serialize transfer {send_struct:?}"))?;
-    send(&serialized).with_context(|| format!("Failed to send close command {serialized:?}"))
+    send(&send_struct).with_context(|| format!("Failed to send close command {send_struct:?}"))?;
+    Ok(())
 }
 
 pub fn daemon_running() -> bool {
@@ -95,37 +169,51 @@ pub fn daemon_running() -> bool {
     }
 }
 
-fn send(buffer: &Vec<u8>) -> anyhow::Result<bool> {
+/// sends the transfer to the daemon and returns the response,
+/// a [`crate::ResponseError`] returned by the daemon is returned as error (use `downcast_ref` to get it)
+fn send(transfer: &Transfer) -> anyhow::Result<ResponseType> {
+    let buffer = bincode::serialize(transfer)
+        .with_context(|| format!("Failed to serialize transfer {transfer:?}"))?;
+
     if *global::DRY.get().expect("DRY not set") {
         debug!("DRY RUN: Would have sent {buffer:?}");
-        return Ok(true);
+        return Ok(match transfer.transfer {
+            TransferType::Dispatch(_) => ResponseType::Dispatch,
+            TransferType::Init(..) => ResponseType::Init,
+            TransferType::Close(_) => ResponseType::Close,
+            TransferType::VersionCheck => {
+                ResponseType::VersionCheck(env!("CARGO_PKG_VERSION").to_string())
+            }
+            TransferType::Open => ResponseType::Open(false),
+            TransferType::Query => {
+                return Err(anyhow::anyhow!("Can't query the daemon in DRY mode"))
+            }
+            TransferType::Subscribe => ResponseType::Subscribe,
+            TransferType::Reload => ResponseType::Reload,
+        });
     }
 
     let path_buf = get_socket_path_buff();
     let path = path_buf.as_path();
     let mut stream = UnixStream::connect(path)
         .with_context(|| format!("Failed to connect to socket {path:?}"))?;
+    // prefixed with the length, as the payload can contain any byte (also newlines)
     stream
-        .write_all(buffer.as_ref())
-        .with_context(|| format!("Failed to write data {buffer:?} to socket {path:?}"))?;
-    stream
-        .write(b"\n")
+        .write_all(&(buffer.len() as u32).to_be_bytes())
+        .and_then(|_| stream.write_all(buffer.as_ref()))
         .with_context(|| format!("Failed to write data {buffer:?} to socket {path:?}"))?;
     stream
         .flush()
         .with_context(|| format!("Failed to flush data {buffer:?} to socket {path:?}"))?;
 
-    let mut reader = BufReader::new(stream);
+    // daemon closes the stream after writing the response
     let mut buffer = Vec::new();
-    reader
-        .read_until(b'\n', &mut buffer)
+    stream
+        .read_to_end(&mut buffer)
         .context("Failed to read data from buffer")?;
-    match buffer.first() {
-        Some(b'1') => Ok(true),
-        Some(b'0') => Err(anyhow::anyhow!("Command failed")),
-        _ => Err(anyhow::anyhow!(format!(
-            "Unknown response {:?} ??",
-            buffer.first()
-        ))),
-    }
+    let response: Response = bincode::deserialize(&buffer).with_context(|| {
+        format!("Unknown response {buffer:?} ?? (daemon version probably doesn't match)")
+    })?;
+    trace!("Received response: {response:?}");
+    Ok(response.response?)
 }
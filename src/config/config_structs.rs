@@ -34,6 +34,7 @@ pub struct Launcher {
     pub show_execs: bool,
     #[default = 300]
     pub animate_launch_time_ms: u64,
+    pub launch_backend: crate::LaunchBackend,
 }
 
 #[derive(SmartDefault, Debug, Deserialize, Serialize)]
@@ -47,7 +48,7 @@ pub struct Gui {
     pub strip_html_from_title: bool,
     #[default = 512]
     pub icon_size: u16,
-    #[default = false]
+    #[default = true]
     pub show_default_icon: bool,
 }
 
@@ -177,6 +178,16 @@ pub enum FilterBy {
     SameClass,
     CurrentWorkspace,
     CurrentMonitor,
+    // regex for the class / title of the windows
+    Class(String),
+    Title(String),
+    ExcludeClass(String),
+    ExcludeTitle(String),
+    // only floating (true) or tiled (false) windows
+    Floating(bool),
+    Fullscreen(bool),
+    Pinned(bool),
+    Xwayland(bool),
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -233,7 +244,7 @@ impl ToKey for KeyMaybeMod {
     fn to_key(&self) -> String {
         match &*self.0.to_ascii_lowercase() {
             "alt" => "alt_l".to_string(),
-            "ctrl" => "ctrl_l".to_string(),
+            "ctrl" => "control_l".to_string(),
             "super" => "super_l".to_string(),
             "shift" => "shift_l".to_string(),
             a => a.to_string(),
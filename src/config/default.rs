@@ -0,0 +1,68 @@
+use crate::config::config_structs::{
+    Bind, Config, General, HoldBindConfig, Mod, OpenHold, PressBindConfig,
+};
+use crate::config::to_ron;
+
+// comments added before the first occurrence of the field (ron can't serialize comments)
+const COMMENTS: &[(&str, &str)] = &[
+    ("disable_toast", "don't show notifications for errors in the daemon or binds"),
+    ("size_factor", "size of the GUI (original_size / 30 * size_factor)"),
+    ("custom_css_path", "css file to customize the GUI, e.g. \"/home/user/.config/hyprswitch/style.css\""),
+    ("launcher", "application launcher, shown in Press binds with show_launcher"),
+    ("items", "maximum number of applications shown in the launcher"),
+    ("default_terminal", "terminal for applications that run in a terminal (a list of known terminals is tried if None)"),
+    ("show_execs", "show the command of the applications in the launcher"),
+    ("animate_launch_time_ms", "duration of the launch animation"),
+    ("launch_backend", "auto, systemd (own scope, keeps running when the daemon restarts), fork (detached from the daemon, stopped with a systemd service) or hyprland (exec dispatcher, opens on the workspace selected in the GUI)"),
+    ("show_title", "show the title of windows instead of their class"),
+    ("workspaces_per_row", "workspaces in one row of the GUI (overflows to the next row)"),
+    ("strip_html_from_title", "remove html (<span>) from workspace names"),
+    ("icon_size", "maximum size of the icons of windows in pixels (smaller windows get smaller icons)"),
+    ("show_default_icon", "show a generic icon for windows without an icon (no icon if false)"),
+    ("binds", "Press: open with modifier + key, switch with the navigate keys and close with return\n\
+               Hold: open with modifier + navigate key, switch while holding the modifier and close by releasing it\n\
+               Simple: switch without the GUI, e.g. Simple(open: (modifier: alt, key: \"tab\"))"),
+    ("modifier", "alt, ctrl, super or shift"),
+    ("key", "key name like in the Hyprland config (modifier names like super open on release of the modifier)"),
+    ("reverse", "Key(\"grave\") or a modifier like Mod(shift)"),
+    ("close_on_reopen", "close the GUI when pressing the open keys again"),
+    ("max_switch_offset", "switch to the n-th next item with the number keys (0 to disable)"),
+    ("monitors", "show the GUI only on these monitors, e.g. [\"DP-1\"] (all if None)"),
+    ("switch_type", "client, workspace or monitor"),
+    ("filter_by", "e.g. [same_class, current_workspace, current_monitor, class(\"regex\"), title(\"regex\"), exclude_class(\"regex\"), exclude_title(\"regex\"), floating(true), fullscreen(false), pinned(true), xwayland(false)]"),
+];
+
+/// default config with a Press and a Hold bind and comments explaining the options
+pub fn default_config() -> anyhow::Result<String> {
+    let config = Config {
+        general: General::default(),
+        binds: vec![
+            Bind::Press(PressBindConfig::default()),
+            Bind::Hold(HoldBindConfig {
+                open: OpenHold { modifier: Mod::Alt },
+                ..Default::default()
+            }),
+        ],
+    };
+    let text = to_ron(&config)?;
+    Ok(add_comments(&text))
+}
+
+fn add_comments(text: &str) -> String {
+    let mut commented = Vec::new();
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some((field, comment)) = COMMENTS.iter().find(|(field, _)| {
+            trimmed.starts_with(&format!("{field}:")) && !commented.contains(field)
+        }) {
+            commented.push(field);
+            for comment in comment.lines() {
+                lines.push(format!("{indent}// {}", comment.trim()));
+            }
+        }
+        lines.push(line.to_string());
+    }
+    lines.join("\n") + "\n"
+}
@@ -1,6 +1,6 @@
 use crate::config::config_structs::{
-    Bind, Config, FilterBy, General, HoldBindConfig, Other, PressBindConfig, Reverse,
-    SimpleBindConfig, ToKey,
+    Bind, Config, FilterBy, HoldBindConfig, Other, PressBindConfig, Reverse, SimpleBindConfig,
+    ToKey,
 };
 use rand::Rng;
 use std::env;
@@ -11,31 +11,49 @@ pub fn create_binds_and_submaps<'a>(
     exe: Option<PathBuf>,
     config: Config,
 ) -> anyhow::Result<Vec<(&'a str, String)>> {
+    let current_exe = get_exe(exe)?;
     let mut keyword_list = Vec::<(&str, String)>::new();
+    generate_daemon_start(&mut keyword_list, &current_exe);
+    generate_binds(&mut keyword_list, &current_exe, config.binds);
+    Ok(keyword_list)
+}
+
+/// creates only the binds and submaps (without starting the daemon), used to register them at runtime
+pub fn create_binds<'a>(
+    exe: Option<PathBuf>,
+    binds: Vec<Bind>,
+) -> anyhow::Result<Vec<(&'a str, String)>> {
+    let current_exe = get_exe(exe)?;
+    let mut keyword_list = Vec::<(&str, String)>::new();
+    generate_binds(&mut keyword_list, &current_exe, binds);
+    Ok(keyword_list)
+}
+
+fn get_exe(exe: Option<PathBuf>) -> anyhow::Result<String> {
     let current_exe = if let Some(exe) = exe {
         exe.into_os_string()
     } else {
         let exe = env::current_exe()?;
         exe.into_os_string()
     };
-    let current_exe = current_exe.to_string_lossy();
+    let current_exe = current_exe
+        .to_string_lossy()
+        .trim_end_matches(" (deleted)")
+        .to_string();
     trace!("current_exe: {}", current_exe);
+    Ok(current_exe)
+}
 
+fn generate_binds(keyword_list: &mut Vec<(&str, String)>, current_exe: &str, binds: Vec<Bind>) {
     let rand_id = rand::rng().random_range(10..=99);
-
-    generate_daemon_start(&mut keyword_list, config.general, &current_exe);
-    for (i, bind) in config.binds.into_iter().enumerate() {
+    for (i, bind) in binds.into_iter().enumerate() {
         let submap_name = format!("hyprswitch-{rand_id}-{i}");
         match bind {
-            Bind::Press(press) => {
-                generate_press(&mut keyword_list, &current_exe, press, submap_name)
-            }
-            Bind::Hold(hold) => generate_hold(&mut keyword_list, &current_exe, hold, submap_name),
-            Bind::Simple(simple) => generate_simple(&mut keyword_list, &current_exe, simple),
+            Bind::Press(press) => generate_press(keyword_list, current_exe, press, submap_name),
+            Bind::Hold(hold) => generate_hold(keyword_list, current_exe, hold, submap_name),
+            Bind::Simple(simple) => generate_simple(keyword_list, current_exe, simple),
         }
     }
-
-    Ok(keyword_list)
 }
 
 fn generate_common_gui(params: &mut Vec<String>, other: &Other) {
@@ -68,10 +86,28 @@ fn generate_other(params: &mut Vec<String>, other: &Other) {
                 FilterBy::CurrentMonitor => "--filter-current-monitor".to_string(),
                 FilterBy::CurrentWorkspace => "--filter-current-workspace".to_string(),
                 FilterBy::SameClass => "--filter-same-class".to_string(),
+                FilterBy::Class(regex) => format!("--filter-class={}", shell_quote(regex)),
+                FilterBy::Title(regex) => format!("--filter-title={}", shell_quote(regex)),
+                FilterBy::ExcludeClass(regex) => {
+                    format!("--exclude-class={}", shell_quote(regex))
+                }
+                FilterBy::ExcludeTitle(regex) => {
+                    format!("--exclude-title={}", shell_quote(regex))
+                }
+                FilterBy::Floating(floating) => format!("--filter-floating={floating}"),
+                FilterBy::Fullscreen(fullscreen) => format!("--filter-fullscreen={fullscreen}"),
+                FilterBy::Pinned(pinned) => format!("--filter-pinned={pinned}"),
+                FilterBy::Xwayland(xwayland) => format!("--filter-xwayland={xwayland}"),
             });
         }
     }
 }
+
+/// exec binds are run with `sh -c`, so regexes and paths need to be quoted
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
 fn generate_simple(
     keyword_list: &mut Vec<(&str, String)>,
     current_exe: &str,
@@ -200,6 +236,7 @@ fn generate_hold(
     let mut params = Vec::<String>::new();
     params.push(format!("--submap={}", submap_name));
     params.push(format!("--reverse-key={}", hold.navigate.reverse));
+    params.push("--show-launcher=false".to_string());
     generate_other(&mut params, &hold.other);
     generate_common_gui(&mut params, &hold.other);
 
@@ -329,60 +366,9 @@ fn generate_hold(
     keyword_list.push(("submap", "reset\n".to_string()));
 }
 
-fn generate_daemon_start(
-    keyword_list: &mut Vec<(&str, String)>,
-    general: General,
-    current_exe: &str,
-) {
-    let mut params = Vec::<String>::new();
-    let mut envs = Vec::<String>::new();
-
-    envs.push(format!("DISABLE_TOASTS={}", general.disable_toast));
-
-    params.push(format!("--size_factor={}", general.size_factor));
-
-    envs.push(format!("SHOW_LAUNCHER={}", general.launcher.enable));
-    envs.push(format!("LAUNCHER_MAX_ITEMS={}", general.launcher.items));
-    envs.push(format!(
-        "SHOW_LAUNCHER_EXECS={}",
-        general.launcher.show_execs
-    ));
-    envs.push(format!(
-        "LAUNCHER_ANIMATE_LAUNCH_TIME={}",
-        general.launcher.animate_launch_time_ms
-    ));
-    if let Some(default_terminal) = general.launcher.default_terminal {
-        envs.push(format!("DEFAULT_TERMINAL={}", default_terminal));
-    }
-
-    params.push(format!("--show-title={}", general.gui.show_title));
-    params.push(format!(
-        "--workspaces-per-row={}",
-        general.gui.workspaces_per_row
-    ));
-    envs.push(format!(
-        "REMOVE_HTML_FROM_WORKSPACE_NAME={}",
-        general.gui.strip_html_from_title
-    ));
-
-    envs.push(format!("ICON_SIZE={}", general.gui.icon_size));
-    envs.push(format!(
-        "SHOW_DEFAULT_ICON={}",
-        general.gui.show_default_icon
-    ));
-    if let Some(custom_css_path) = general.custom_css_path {
-        params.push(format!("--custom-css={}", custom_css_path));
-    }
-
-    keyword_list.push((
-        "exec-once",
-        format!(
-            "{} {} init {}\n",
-            envs.join(" "),
-            current_exe,
-            params.join(" ")
-        ),
-    ));
+fn generate_daemon_start(keyword_list: &mut Vec<(&str, String)>, current_exe: &str) {
+    // all settings are read from the config file by the daemon (and reloaded when it changes)
+    keyword_list.push(("exec-once", format!("{} init --config\n", current_exe)));
 }
 
 pub fn export(list: Vec<(&str, String)>) -> String {
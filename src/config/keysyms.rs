@@ -0,0 +1,573 @@
+/// checks if `name` is a keysym name that xkbcommon resolves (Hyprland looks keys up case-insensitive)
+///
+/// contains the keysyms of the latin layouts, function, keypad, modifier, ISO, dead and XF86 keys,
+/// numeric keysyms (`0x1234`) and unicode keysyms (`U20AC`) are accepted too
+pub(super) fn is_keysym(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    if lower.len() == 1 {
+        return lower.chars().all(|c| c.is_ascii_alphanumeric());
+    }
+    if let Some(hex) = lower.strip_prefix("0x") {
+        return (1..=8).contains(&hex.len()) && u32::from_str_radix(hex, 16).is_ok();
+    }
+    if let Some(hex) = lower.strip_prefix('u') {
+        if (4..=6).contains(&hex.len()) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return u32::from_str_radix(hex, 16).is_ok_and(|c| c <= 0x10ffff);
+        }
+    }
+    let numbered = |prefix: &str, max: u8| {
+        lower
+            .strip_prefix(prefix)
+            .filter(|n| !n.starts_with('0'))
+            .and_then(|n| n.parse::<u8>().ok())
+            .is_some_and(|n| (1..=max).contains(&n))
+    };
+    if numbered("f", 35)
+        || numbered("l", 10)
+        || numbered("r", 15)
+        || numbered("kp_f", 4)
+        || numbered("xf86switch_vt_", 12)
+    {
+        return true;
+    }
+    if let Some(n) = lower.strip_prefix("kp_") {
+        if n.len() == 1 && n.chars().all(|c| c.is_ascii_digit()) {
+            return true;
+        }
+    }
+    if let Some(n) = lower.strip_prefix("xf86launch") {
+        if n.len() == 1 && n.chars().all(|c| c.is_ascii_hexdigit()) {
+            return true;
+        }
+    }
+    KEYSYMS
+        .iter()
+        .any(|keysym| keysym.eq_ignore_ascii_case(name))
+}
+
+// names of xkbcommon (xkbcommon-keysyms.h) including the aliases (e.g. Page_Up for Prior)
+const KEYSYMS: &[&str] = &[
+    // tty and cursor keys
+    "BackSpace",
+    "Tab",
+    "Linefeed",
+    "Clear",
+    "Return",
+    "Pause",
+    "Scroll_Lock",
+    "Sys_Req",
+    "Escape",
+    "Delete",
+    "VoidSymbol",
+    "Multi_key",
+    "Codeinput",
+    "SingleCandidate",
+    "MultipleCandidate",
+    "PreviousCandidate",
+    "Home",
+    "Left",
+    "Up",
+    "Right",
+    "Down",
+    "Prior",
+    "Page_Up",
+    "Next",
+    "Page_Down",
+    "End",
+    "Begin",
+    "Select",
+    "Print",
+    "Execute",
+    "Insert",
+    "Undo",
+    "Redo",
+    "Menu",
+    "Find",
+    "Cancel",
+    "Help",
+    "Break",
+    "Mode_switch",
+    "script_switch",
+    "Num_Lock",
+    // japanese and korean input
+    "Kanji",
+    "Muhenkan",
+    "Henkan_Mode",
+    "Henkan",
+    "Romaji",
+    "Hiragana",
+    "Katakana",
+    "Hiragana_Katakana",
+    "Zenkaku",
+    "Hankaku",
+    "Zenkaku_Hankaku",
+    "Touroku",
+    "Massyo",
+    "Kana_Lock",
+    "Kana_Shift",
+    "Eisu_Shift",
+    "Eisu_toggle",
+    "Hangul",
+    "Hangul_Start",
+    "Hangul_End",
+    "Hangul_Hanja",
+    "Hangul_Jamo",
+    "Hangul_Romaja",
+    "Hangul_Jeonja",
+    "Hangul_Banja",
+    "Hangul_PreHanja",
+    "Hangul_PostHanja",
+    "Hangul_Special",
+    // keypad (digits and F1-F4 are checked separately)
+    "KP_Space",
+    "KP_Tab",
+    "KP_Enter",
+    "KP_Home",
+    "KP_Left",
+    "KP_Up",
+    "KP_Right",
+    "KP_Down",
+    "KP_Prior",
+    "KP_Page_Up",
+    "KP_Next",
+    "KP_Page_Down",
+    "KP_End",
+    "KP_Begin",
+    "KP_Insert",
+    "KP_Delete",
+    "KP_Equal",
+    "KP_Multiply",
+    "KP_Add",
+    "KP_Separator",
+    "KP_Subtract",
+    "KP_Decimal",
+    "KP_Divide",
+    // modifiers
+    "Shift_L",
+    "Shift_R",
+    "Control_L",
+    "Control_R",
+    "Caps_Lock",
+    "Shift_Lock",
+    "Meta_L",
+    "Meta_R",
+    "Alt_L",
+    "Alt_R",
+    "Super_L",
+    "Super_R",
+    "Hyper_L",
+    "Hyper_R",
+    // ISO 9995
+    "ISO_Lock",
+    "ISO_Level2_Latch",
+    "ISO_Level3_Shift",
+    "ISO_Level3_Latch",
+    "ISO_Level3_Lock",
+    "ISO_Level5_Shift",
+    "ISO_Level5_Latch",
+    "ISO_Level5_Lock",
+    "ISO_Group_Shift",
+    "ISO_Group_Latch",
+    "ISO_Group_Lock",
+    "ISO_Next_Group",
+    "ISO_Next_Group_Lock",
+    "ISO_Prev_Group",
+    "ISO_Prev_Group_Lock",
+    "ISO_First_Group",
+    "ISO_First_Group_Lock",
+    "ISO_Last_Group",
+    "ISO_Last_Group_Lock",
+    "ISO_Left_Tab",
+    "ISO_Move_Line_Up",
+    "ISO_Move_Line_Down",
+    "ISO_Partial_Line_Up",
+    "ISO_Partial_Line_Down",
+    "ISO_Partial_Space_Left",
+    "ISO_Partial_Space_Right",
+    "ISO_Set_Margin_Left",
+    "ISO_Set_Margin_Right",
+    "ISO_Release_Margin_Left",
+    "ISO_Release_Margin_Right",
+    "ISO_Release_Both_Margins",
+    "ISO_Fast_Cursor_Left",
+    "ISO_Fast_Cursor_Right",
+    "ISO_Fast_Cursor_Up",
+    "ISO_Fast_Cursor_Down",
+    "ISO_Continuous_Underline",
+    "ISO_Discontinuous_Underline",
+    "ISO_Emphasize",
+    "ISO_Center_Object",
+    "ISO_Enter",
+    // dead keys
+    "dead_grave",
+    "dead_acute",
+    "dead_circumflex",
+    "dead_tilde",
+    "dead_perispomeni",
+    "dead_macron",
+    "dead_breve",
+    "dead_abovedot",
+    "dead_diaeresis",
+    "dead_abovering",
+    "dead_doubleacute",
+    "dead_caron",
+    "dead_cedilla",
+    "dead_ogonek",
+    "dead_iota",
+    "dead_voiced_sound",
+    "dead_semivoiced_sound",
+    "dead_belowdot",
+    "dead_hook",
+    "dead_horn",
+    "dead_stroke",
+    "dead_abovecomma",
+    "dead_psili",
+    "dead_abovereversedcomma",
+    "dead_dasia",
+    "dead_doublegrave",
+    "dead_belowring",
+    "dead_belowmacron",
+    "dead_belowcircumflex",
+    "dead_belowtilde",
+    "dead_belowbreve",
+    "dead_belowdiaeresis",
+    "dead_invertedbreve",
+    "dead_belowcomma",
+    "dead_currency",
+    "dead_greek",
+    "dead_a",
+    "dead_e",
+    "dead_i",
+    "dead_o",
+    "dead_u",
+    "dead_small_schwa",
+    "dead_capital_schwa",
+    // latin 1 (single letters and digits are checked separately)
+    "space",
+    "exclam",
+    "quotedbl",
+    "numbersign",
+    "dollar",
+    "percent",
+    "ampersand",
+    "apostrophe",
+    "quoteright",
+    "parenleft",
+    "parenright",
+    "asterisk",
+    "plus",
+    "comma",
+    "minus",
+    "period",
+    "slash",
+    "colon",
+    "semicolon",
+    "less",
+    "equal",
+    "greater",
+    "question",
+    "at",
+    "bracketleft",
+    "backslash",
+    "bracketright",
+    "asciicircum",
+    "underscore",
+    "grave",
+    "quoteleft",
+    "braceleft",
+    "bar",
+    "braceright",
+    "asciitilde",
+    "nobreakspace",
+    "exclamdown",
+    "cent",
+    "sterling",
+    "currency",
+    "yen",
+    "brokenbar",
+    "section",
+    "diaeresis",
+    "copyright",
+    "ordfeminine",
+    "guillemotleft",
+    "guillemetleft",
+    "notsign",
+    "hyphen",
+    "registered",
+    "macron",
+    "degree",
+    "plusminus",
+    "twosuperior",
+    "threesuperior",
+    "acute",
+    "mu",
+    "paragraph",
+    "periodcentered",
+    "cedilla",
+    "onesuperior",
+    "masculine",
+    "ordmasculine",
+    "guillemotright",
+    "guillemetright",
+    "onequarter",
+    "onehalf",
+    "threequarters",
+    "questiondown",
+    "agrave",
+    "aacute",
+    "acircumflex",
+    "atilde",
+    "adiaeresis",
+    "aring",
+    "ae",
+    "ccedilla",
+    "egrave",
+    "eacute",
+    "ecircumflex",
+    "ediaeresis",
+    "igrave",
+    "iacute",
+    "icircumflex",
+    "idiaeresis",
+    "eth",
+    "ntilde",
+    "ograve",
+    "oacute",
+    "ocircumflex",
+    "otilde",
+    "odiaeresis",
+    "multiply",
+    "division",
+    "oslash",
+    "ooblique",
+    "ugrave",
+    "uacute",
+    "ucircumflex",
+    "udiaeresis",
+    "yacute",
+    "thorn",
+    "ssharp",
+    "ydiaeresis",
+    // latin 2, 3, 4 and 9
+    "aogonek",
+    "breve",
+    "lstroke",
+    "lcaron",
+    "sacute",
+    "scaron",
+    "scedilla",
+    "tcaron",
+    "zacute",
+    "zcaron",
+    "zabovedot",
+    "ogonek",
+    "caron",
+    "doubleacute",
+    "racute",
+    "abreve",
+    "lacute",
+    "cacute",
+    "ccaron",
+    "eogonek",
+    "ecaron",
+    "dcaron",
+    "dstroke",
+    "nacute",
+    "ncaron",
+    "odoubleacute",
+    "rcaron",
+    "uring",
+    "udoubleacute",
+    "tcedilla",
+    "abovedot",
+    "hstroke",
+    "hcircumflex",
+    "iabovedot",
+    "idotless",
+    "gbreve",
+    "jcircumflex",
+    "cabovedot",
+    "ccircumflex",
+    "gabovedot",
+    "gcircumflex",
+    "ubreve",
+    "scircumflex",
+    "kra",
+    "kappa",
+    "rcedilla",
+    "itilde",
+    "lcedilla",
+    "emacron",
+    "gcedilla",
+    "tslash",
+    "eng",
+    "amacron",
+    "iogonek",
+    "eabovedot",
+    "imacron",
+    "ncedilla",
+    "omacron",
+    "kcedilla",
+    "uogonek",
+    "utilde",
+    "umacron",
+    "oe",
+    "EuroSign",
+    // XF86 (media and laptop keys, XF86Launch0-F and XF86Switch_VT_1-12 are checked separately)
+    "XF86ModeLock",
+    "XF86MonBrightnessUp",
+    "XF86MonBrightnessDown",
+    "XF86MonBrightnessCycle",
+    "XF86KbdLightOnOff",
+    "XF86KbdBrightnessUp",
+    "XF86KbdBrightnessDown",
+    "XF86Standby",
+    "XF86AudioLowerVolume",
+    "XF86AudioMute",
+    "XF86AudioRaiseVolume",
+    "XF86AudioPlay",
+    "XF86AudioStop",
+    "XF86AudioPrev",
+    "XF86AudioNext",
+    "XF86AudioPause",
+    "XF86AudioRecord",
+    "XF86AudioRewind",
+    "XF86AudioForward",
+    "XF86AudioRepeat",
+    "XF86AudioRandomPlay",
+    "XF86AudioCycleTrack",
+    "XF86AudioMedia",
+    "XF86AudioMicMute",
+    "XF86AudioPreset",
+    "XF86HomePage",
+    "XF86Mail",
+    "XF86Start",
+    "XF86Search",
+    "XF86Calculator",
+    "XF86Calculater",
+    "XF86Memo",
+    "XF86ToDoList",
+    "XF86Calendar",
+    "XF86PowerDown",
+    "XF86ContrastAdjust",
+    "XF86RockerUp",
+    "XF86RockerDown",
+    "XF86RockerEnter",
+    "XF86Back",
+    "XF86Forward",
+    "XF86Stop",
+    "XF86Refresh",
+    "XF86PowerOff",
+    "XF86WakeUp",
+    "XF86Eject",
+    "XF86ScreenSaver",
+    "XF86WWW",
+    "XF86Sleep",
+    "XF86Favorites",
+    "XF86MyComputer",
+    "XF86VendorHome",
+    "XF86LightBulb",
+    "XF86Shop",
+    "XF86History",
+    "XF86OpenURL",
+    "XF86AddFavorite",
+    "XF86HotLinks",
+    "XF86BrightnessAdjust",
+    "XF86Finance",
+    "XF86Community",
+    "XF86BackForward",
+    "XF86ApplicationLeft",
+    "XF86ApplicationRight",
+    "XF86Book",
+    "XF86CD",
+    "XF86Clear",
+    "XF86Close",
+    "XF86Copy",
+    "XF86Cut",
+    "XF86Display",
+    "XF86DOS",
+    "XF86Documents",
+    "XF86Excel",
+    "XF86Explorer",
+    "XF86Game",
+    "XF86Go",
+    "XF86iTouch",
+    "XF86LogOff",
+    "XF86Market",
+    "XF86Meeting",
+    "XF86MenuKB",
+    "XF86MenuPB",
+    "XF86MySites",
+    "XF86New",
+    "XF86News",
+    "XF86OfficeHome",
+    "XF86Open",
+    "XF86Option",
+    "XF86Paste",
+    "XF86Phone",
+    "XF86Q",
+    "XF86Reply",
+    "XF86Reload",
+    "XF86RotateWindows",
+    "XF86RotationPB",
+    "XF86RotationKB",
+    "XF86RotationLockToggle",
+    "XF86Save",
+    "XF86ScrollUp",
+    "XF86ScrollDown",
+    "XF86ScrollClick",
+    "XF86Send",
+    "XF86Spell",
+    "XF86SplitScreen",
+    "XF86Support",
+    "XF86TaskPane",
+    "XF86Terminal",
+    "XF86Tools",
+    "XF86Travel",
+    "XF86UserPB",
+    "XF86User1KB",
+    "XF86User2KB",
+    "XF86Video",
+    "XF86WheelButton",
+    "XF86Word",
+    "XF86Xfer",
+    "XF86ZoomIn",
+    "XF86ZoomOut",
+    "XF86Away",
+    "XF86Messenger",
+    "XF86WebCam",
+    "XF86MailForward",
+    "XF86Pictures",
+    "XF86Music",
+    "XF86Battery",
+    "XF86Bluetooth",
+    "XF86WLAN",
+    "XF86UWB",
+    "XF86WWAN",
+    "XF86RFKill",
+    "XF86Subtitle",
+    "XF86CycleAngle",
+    "XF86FrameBack",
+    "XF86FrameForward",
+    "XF86Time",
+    "XF86Select",
+    "XF86View",
+    "XF86TopMenu",
+    "XF86Red",
+    "XF86Green",
+    "XF86Yellow",
+    "XF86Blue",
+    "XF86Suspend",
+    "XF86Hibernate",
+    "XF86TouchpadToggle",
+    "XF86TouchpadOn",
+    "XF86TouchpadOff",
+    "XF86Keyboard",
+    "XF86FullScreen",
+    "XF86Ungrab",
+    "XF86ClearGrab",
+    "XF86Next_VMode",
+    "XF86Prev_VMode",
+    "XF86LogWindowTree",
+    "XF86LogGrabInfo",
+];
@@ -0,0 +1,160 @@
+use crate::config::config_structs::{
+    Bind, ClosePress, FilterBy, HoldBindConfig, Mod, Navigate, OpenHold, OpenPress, OpenSimple,
+    Other, PressBindConfig, Reverse, SimpleBindConfig, SwitchType,
+};
+use crate::config::to_ron;
+use crate::{
+    CloseType, DispatchConfig, Filter, GuiConfig, ModKey, ReverseKey, SimpleConfig, SubmapConfig,
+};
+use tracing::warn;
+
+/// converts a bind opening the GUI with `hyprswitch gui` into a Press (`--close default`) or Hold (`--close mod-key-release`) bind
+///
+/// modifier and key are the keys of the bind line in the Hyprland config
+pub fn migrate_gui(
+    modifier: &str,
+    key: &str,
+    simple_config: SimpleConfig,
+    gui_config: GuiConfig,
+    submap_config: SubmapConfig,
+) -> anyhow::Result<String> {
+    let SubmapConfig::Config {
+        mod_key,
+        key: forward,
+        close,
+        reverse_key,
+    } = submap_config
+    else {
+        anyhow::bail!("Binds using --submap can't be migrated (the binds in the submap are unknown), use --mod-key and --key instead");
+    };
+    let navigate = Navigate {
+        forward,
+        reverse: match reverse_key {
+            ReverseKey::Mod(mod_key) => Reverse::Mod(mod_from_mod_key(mod_key)),
+            ReverseKey::Key(key) => Reverse::Key(key),
+        },
+        ..Default::default()
+    };
+    // the launcher wasn't shown without --show-launcher
+    let show_launcher = gui_config.show_launcher.unwrap_or(false);
+    if gui_config.launcher_max_items.is_some()
+        || gui_config.show_launcher_execs.is_some()
+        || gui_config.default_terminal.is_some()
+    {
+        warn!("Launcher options of binds are not supported in the config, set them in general.launcher instead");
+    }
+    let other = Other {
+        max_switch_offset: gui_config.max_switch_offset as u32,
+        hide_active_window_border: gui_config.hide_active_window_border,
+        monitors: gui_config.monitors,
+        show_workspaces_on_all_monitors: gui_config.show_workspaces_on_all_monitors,
+        ..other_from_simple(simple_config)
+    };
+
+    let bind = match close {
+        CloseType::Default => Bind::Press(PressBindConfig {
+            show_launcher,
+            open: OpenPress {
+                modifier: parse_mod(modifier)?,
+                key: key.into(),
+            },
+            navigate,
+            close: ClosePress::default(),
+            other,
+        }),
+        // opened by holding the mod key and pressing the forward / reverse key
+        CloseType::ModKeyRelease => Bind::Hold(HoldBindConfig {
+            open: OpenHold {
+                modifier: mod_from_mod_key(mod_key),
+            },
+            navigate,
+            other,
+            ..Default::default()
+        }),
+    };
+    to_ron(&bind)
+}
+
+/// converts a bind switching with `hyprswitch simple` into a Simple bind
+pub fn migrate_simple(
+    modifier: &str,
+    key: &str,
+    simple_config: SimpleConfig,
+    dispatch_config: DispatchConfig,
+) -> anyhow::Result<String> {
+    let bind = Bind::Simple(SimpleBindConfig {
+        reverse: dispatch_config.reverse,
+        offset: dispatch_config.offset,
+        open: OpenSimple {
+            modifier: parse_mod(modifier)?,
+            key: key.into(),
+        },
+        other: other_from_simple(simple_config),
+    });
+    to_ron(&bind)
+}
+
+fn other_from_simple(simple_config: SimpleConfig) -> Other {
+    if simple_config.ignore_monitors || simple_config.ignore_workspaces {
+        warn!("--ignore-monitors and --ignore-workspaces are not supported in the config, ignoring them");
+    }
+    let mut filter_by = Vec::new();
+    if simple_config.filter_same_class {
+        filter_by.push(FilterBy::SameClass);
+    }
+    if simple_config.filter_current_workspace {
+        filter_by.push(FilterBy::CurrentWorkspace);
+    }
+    if simple_config.filter_current_monitor {
+        filter_by.push(FilterBy::CurrentMonitor);
+    }
+    filter_by.extend(
+        simple_config
+            .filters
+            .into_iter()
+            .map(|filter| match filter {
+                Filter::Class(regex) => FilterBy::Class(regex),
+                Filter::Title(regex) => FilterBy::Title(regex),
+                Filter::ExcludeClass(regex) => FilterBy::ExcludeClass(regex),
+                Filter::ExcludeTitle(regex) => FilterBy::ExcludeTitle(regex),
+                Filter::Floating(floating) => FilterBy::Floating(floating),
+                Filter::Fullscreen(fullscreen) => FilterBy::Fullscreen(fullscreen),
+                Filter::Pinned(pinned) => FilterBy::Pinned(pinned),
+                Filter::Xwayland(xwayland) => FilterBy::Xwayland(xwayland),
+            }),
+    );
+
+    Other {
+        switch_type: match simple_config.switch_type {
+            crate::SwitchType::Client => SwitchType::Client,
+            crate::SwitchType::Workspace => SwitchType::Workspace,
+            crate::SwitchType::Monitor => SwitchType::Monitor,
+        },
+        sort_by_recent: simple_config.sort_recent,
+        include_special_workspaces: simple_config.include_special_workspaces,
+        filter_by: (!filter_by.is_empty()).then_some(filter_by),
+        ..Default::default()
+    }
+}
+
+fn mod_from_mod_key(mod_key: ModKey) -> Mod {
+    match mod_key {
+        ModKey::AltL | ModKey::AltR => Mod::Alt,
+        ModKey::CtrlL | ModKey::CtrlR => Mod::Ctrl,
+        ModKey::SuperL | ModKey::SuperR => Mod::Super,
+        ModKey::ShiftL | ModKey::ShiftR => Mod::Shift,
+    }
+}
+
+/// parses the modifier of a bind line (names like in the Hyprland config)
+fn parse_mod(modifier: &str) -> anyhow::Result<Mod> {
+    match &*modifier.trim().to_ascii_lowercase() {
+        "alt" | "mod1" => Ok(Mod::Alt),
+        "ctrl" | "control" => Ok(Mod::Ctrl),
+        "super" | "win" | "logo" | "mod4" => Ok(Mod::Super),
+        "shift" => Ok(Mod::Shift),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported modifier {modifier:?} (only a single alt, ctrl, super or shift is supported, variables like $mainMod need to be replaced)"
+        )),
+    }
+}
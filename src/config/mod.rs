@@ -1,36 +1,110 @@
-use crate::config::config_structs::Config;
+use crate::config::config_structs::{Config, General};
+use crate::InitConfig;
 use anyhow::Context;
 use ron::extensions::Extensions;
+use ron::ser::PrettyConfig;
 use ron::Options;
+use serde::Serialize;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{span, Level};
 
-pub use generate::create_binds_and_submaps;
+pub use default::default_config;
 pub use generate::export;
+pub use generate::{create_binds, create_binds_and_submaps};
+pub use migrate::{migrate_gui, migrate_simple};
 pub use validate::validate;
 
 mod config_structs;
+mod default;
 mod generate;
+mod keysyms;
+mod migrate;
 mod validate;
 
-pub fn load() -> anyhow::Result<Config> {
+/// loads the config from `path` or the default path (see [`get_path`])
+pub fn load(path: Option<&Path>) -> anyhow::Result<Config> {
+    match path {
+        Some(path) => load_from(path),
+        None => load_from(&get_path().context("Failed to get config path")?),
+    }
+}
+
+pub fn load_from(config: &Path) -> anyhow::Result<Config> {
     let _span = span!(Level::TRACE, "load_config").entered();
-    let config = get_path().context("Failed to get config path")?;
-    let options = Options::default()
-        .with_default_extension(Extensions::IMPLICIT_SOME)
-        .with_default_extension(Extensions::UNWRAP_NEWTYPES)
-        .with_default_extension(Extensions::UNWRAP_VARIANT_NEWTYPES);
-    let file = std::fs::File::open(&config)
+    let file = std::fs::File::open(config)
         .with_context(|| format!("Failed to open config at ({config:?})"))?;
-    let config: Config = options
+    let config: Config = options()
         .from_reader(file)
         .context("Failed to read config.ron")?;
 
     Ok(config)
 }
 
-fn get_path() -> Option<PathBuf> {
+/// loads and validates the config file and applies its general settings to the [`InitConfig`] (the overrides of the CLI and env variables take precedence)
+pub fn load_init_config(path: &Path, init_config: &InitConfig) -> anyhow::Result<InitConfig> {
+    let config = load_from(path)?;
+    validate(&config).context("Failed to validate config")?;
+    let binds = if init_config.register_binds {
+        create_binds(None, config.binds)
+            .context("Failed to create binds")?
+            .into_iter()
+            .map(|(keyword, value)| (keyword.to_string(), value.trim().to_string()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let init_config = InitConfig {
+        binds,
+        ..apply_general(&config.general, init_config)
+    }
+    .apply_overrides();
+    init_config.validate()?;
+    Ok(init_config)
+}
+
+fn apply_general(general: &General, init_config: &InitConfig) -> InitConfig {
+    InitConfig {
+        custom_css: general.custom_css_path.as_ref().map(PathBuf::from),
+        show_title: general.gui.show_title,
+        workspaces_per_row: general.gui.workspaces_per_row,
+        size_factor: general.size_factor,
+        show_launcher: general.launcher.enable,
+        launcher_max_items: general.launcher.items as usize,
+        show_launcher_execs: general.launcher.show_execs,
+        launcher_animate_launch_time: general.launcher.animate_launch_time_ms,
+        default_terminal: general.launcher.default_terminal.clone(),
+        remove_html_from_workspace_name: general.gui.strip_html_from_title,
+        icon_size: general.gui.icon_size,
+        show_default_icon: general.gui.show_default_icon,
+        launch_backend: general.launcher.launch_backend,
+        disable_toasts: general.disable_toast,
+        config_file: init_config.config_file.clone(),
+        register_binds: init_config.register_binds,
+        binds: Vec::new(),
+        overrides: init_config.overrides.clone(),
+    }
+}
+
+pub fn parse(text: &str) -> anyhow::Result<Config> {
+    options().from_str(text).context("Failed to parse config")
+}
+
+/// serializes with the same extensions as used for parsing (no `Some(...)`, newtype wrappers or `#![enable(...)]`)
+fn to_ron<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    options()
+        .to_string_pretty(value, PrettyConfig::default())
+        .context("Failed to serialize config")
+}
+
+fn options() -> Options {
+    Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .with_default_extension(Extensions::UNWRAP_NEWTYPES)
+        .with_default_extension(Extensions::UNWRAP_VARIANT_NEWTYPES)
+}
+
+pub fn get_path() -> Option<PathBuf> {
     env::var_os("HYPRSWITCH_CONFIG_FILE")
         .map(PathBuf::from)
         .or_else(|| {
@@ -1,5 +1,318 @@
-use crate::config::config_structs::Config;
+use crate::config::config_structs::{Bind, Config, FilterBy, Mod, Navigate, Other, Reverse, ToKey};
+use crate::config::keysyms::is_keysym;
+use hyprland::data::Monitors;
+use hyprland::prelude::HyprData;
+use regex::Regex;
+use std::fmt;
+use std::fmt::Display;
+use tracing::warn;
 
-pub fn validate(_config: &Config) -> anyhow::Result<()> {
+/// error in a field of the config, path is like `binds[2].navigate.reverse`
+#[derive(Debug, PartialEq)]
+struct ValidationError {
+    path: String,
+    message: String,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+pub fn validate(config: &Config) -> anyhow::Result<()> {
+    // monitors are only checked if hyprland is running
+    let monitors = match Monitors::get() {
+        Ok(monitors) => Some(monitors.into_iter().map(|m| m.name).collect::<Vec<_>>()),
+        Err(e) => {
+            warn!("Unable to get monitors, not checking monitor names: {e}");
+            None
+        }
+    };
+    let errors = check(config, monitors.as_deref());
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "Found {} errors in the config:\n{}",
+            errors.len(),
+            errors
+                .iter()
+                .map(|e| format!("- {e}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
     Ok(())
 }
+
+fn check(config: &Config, monitors: Option<&[String]>) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let mut error = |path: String, message: String| errors.push(ValidationError { path, message });
+
+    if config.general.gui.workspaces_per_row == 0 {
+        error(
+            "general.gui.workspaces_per_row".to_string(),
+            "must be at least 1".to_string(),
+        );
+    }
+
+    // (index, path, normalized keys) of every bind that opens the GUI or switches
+    let mut open_binds = Vec::<(usize, String, String)>::new();
+    for (i, bind) in config.binds.iter().enumerate() {
+        let path = |field: &str| format!("binds[{i}].{field}");
+        let mut keys = Vec::<(String, &Mod, Option<&Mod>, String)>::new();
+        match bind {
+            Bind::Press(press) => {
+                check_key(&mut error, path("open.key"), &press.open.key.to_key());
+                keys.push((
+                    path("open.key"),
+                    &press.open.modifier,
+                    None,
+                    press.open.key.to_key(),
+                ));
+                check_navigate(&mut error, &path, &press.navigate, &press.open.modifier);
+                check_gui(&mut error, &path, &press.other, monitors);
+            }
+            Bind::Hold(hold) => {
+                // the gui is opened with the navigate keys
+                keys.push((
+                    path("navigate.forward"),
+                    &hold.open.modifier,
+                    None,
+                    hold.navigate.forward.clone(),
+                ));
+                match &hold.navigate.reverse {
+                    Reverse::Key(key) => keys.push((
+                        path("navigate.reverse"),
+                        &hold.open.modifier,
+                        None,
+                        key.clone(),
+                    )),
+                    Reverse::Mod(modifier) => keys.push((
+                        path("navigate.reverse"),
+                        &hold.open.modifier,
+                        Some(modifier),
+                        hold.navigate.forward.clone(),
+                    )),
+                }
+                check_navigate(&mut error, &path, &hold.navigate, &hold.open.modifier);
+                check_gui(&mut error, &path, &hold.other, monitors);
+            }
+            Bind::Simple(simple) => {
+                check_key(&mut error, path("open.key"), &simple.open.key.to_key());
+                keys.push((
+                    path("open.key"),
+                    &simple.open.modifier,
+                    None,
+                    simple.open.key.to_key(),
+                ));
+                check_filters(&mut error, &path, &simple.other);
+            }
+        }
+
+        for (field, modifier, extra_modifier, key) in keys {
+            let mut mods = vec![modifier.to_string()];
+            mods.extend(extra_modifier.map(Mod::to_string));
+            mods.sort();
+            mods.dedup();
+            let normalized = format!("{} + {}", mods.join(" + "), key.to_ascii_lowercase());
+            if let Some((other, _, _)) = open_binds
+                .iter()
+                .find(|(index, _, keys)| *index != i && *keys == normalized)
+            {
+                error(
+                    field.clone(),
+                    format!("{normalized} is already used to open binds[{other}]"),
+                );
+            }
+            open_binds.push((i, field, normalized));
+        }
+    }
+    errors
+}
+
+fn check_navigate(
+    error: &mut impl FnMut(String, String),
+    path: &impl Fn(&str) -> String,
+    navigate: &Navigate,
+    open_modifier: &Mod,
+) {
+    check_key(error, path("navigate.forward"), &navigate.forward);
+    match &navigate.reverse {
+        Reverse::Key(key) => check_key(error, path("navigate.reverse"), key),
+        Reverse::Mod(modifier) => {
+            if modifier.to_string() == open_modifier.to_string() {
+                error(
+                    path("navigate.reverse"),
+                    format!("reverse modifier is the same as the open modifier ({modifier})"),
+                );
+            }
+        }
+    }
+}
+
+/// checks the options that are passed to the GUI
+fn check_gui(
+    error: &mut impl FnMut(String, String),
+    path: &impl Fn(&str) -> String,
+    other: &Other,
+    monitors: Option<&[String]>,
+) {
+    if other.max_switch_offset > 9 {
+        error(
+            path("other.max_switch_offset"),
+            format!("must be at most 9 (is {})", other.max_switch_offset),
+        );
+    }
+    if let (Some(configured), Some(monitors)) = (&other.monitors, monitors) {
+        for (j, monitor) in configured.iter().enumerate() {
+            if !monitors.contains(monitor) {
+                error(
+                    path(&format!("other.monitors[{j}]")),
+                    format!(
+                        "monitor {monitor:?} doesn't exist (available: {})",
+                        monitors.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+    check_filters(error, path, other);
+}
+
+fn check_filters(
+    error: &mut impl FnMut(String, String),
+    path: &impl Fn(&str) -> String,
+    other: &Other,
+) {
+    for (j, filter) in other.filter_by.iter().flatten().enumerate() {
+        if let FilterBy::Class(regex)
+        | FilterBy::Title(regex)
+        | FilterBy::ExcludeClass(regex)
+        | FilterBy::ExcludeTitle(regex) = filter
+        {
+            if let Err(e) = Regex::new(regex) {
+                error(
+                    path(&format!("other.filter_by[{j}]")),
+                    format!("invalid regex {regex:?}: {e}"),
+                );
+            }
+        }
+    }
+}
+
+fn check_key(error: &mut impl FnMut(String, String), path: String, key: &str) {
+    if !is_valid_key(key) {
+        error(path, format!("invalid key name {key:?}"));
+    }
+}
+
+/// checks a key like Hyprland does (keysym names are case-insensitive)
+fn is_valid_key(key: &str) -> bool {
+    if let Some(code) = key.strip_prefix("code:") {
+        return code.parse::<u32>().is_ok();
+    }
+    if let Some(button) = key.strip_prefix("mouse:") {
+        return button.parse::<u32>().is_ok();
+    }
+    if matches!(
+        key,
+        "mouse_up" | "mouse_down" | "mouse_left" | "mouse_right" | "catchall"
+    ) || key.starts_with("switch:")
+    {
+        return true;
+    }
+    is_keysym(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse;
+
+    fn errors(config: &str, monitors: &[&str]) -> Vec<String> {
+        let config = parse(config).expect("Failed to parse config");
+        let monitors = monitors.iter().map(|m| m.to_string()).collect::<Vec<_>>();
+        check(&config, Some(&monitors))
+            .into_iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn example_config_is_valid() {
+        let errors = errors(
+            include_str!("../../config/hyprswitch.ron"),
+            &["HDMI-0", "DP-1"],
+        );
+        assert_eq!(errors, Vec::<String>::new());
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        let config = crate::config::default_config().expect("Failed to create default config");
+        assert_eq!(errors(&config, &[]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn keys() {
+        assert!(is_valid_key("tab"));
+        assert!(is_valid_key("TAB"));
+        assert!(is_valid_key("super_l"));
+        assert!(is_valid_key("SUPER_L"));
+        assert!(is_valid_key("control_l"));
+        assert!(is_valid_key("grave"));
+        assert!(is_valid_key("Page_Up"));
+        assert!(is_valid_key("F12"));
+        assert!(is_valid_key("KP_7"));
+        assert!(is_valid_key("XF86AudioRaiseVolume"));
+        assert!(is_valid_key("XF86Launch5"));
+        assert!(is_valid_key("odiaeresis"));
+        assert!(is_valid_key("a"));
+        assert!(is_valid_key("U20AC"));
+        assert!(is_valid_key("0xff09"));
+        assert!(is_valid_key("code:23"));
+        assert!(is_valid_key("mouse:272"));
+        assert!(is_valid_key("mouse_down"));
+        assert!(!is_valid_key("code:tab"));
+        assert!(!is_valid_key("tabb"));
+        assert!(!is_valid_key("supr_l"));
+        assert!(!is_valid_key("ctrl_l"));
+        assert!(!is_valid_key("F36"));
+        assert!(!is_valid_key(","));
+        assert!(!is_valid_key(""));
+    }
+
+    #[test]
+    fn reports_bind_and_field() {
+        let errors = errors(
+            r#"Config(
+                general: (gui: (workspaces_per_row: 0)),
+                binds: [
+                    Press(open: (modifier: super, key: "tab")),
+                    Hold(
+                        open: (modifier: alt),
+                        navigate: (forward: "tabb", reverse: Mod(alt)),
+                        other: (max_switch_offset: 10, monitors: ["DP-1", "DP-9"]),
+                    ),
+                    Simple(
+                        open: (modifier: super, key: "Tab"),
+                        other: (filter_by: [class("(unclosed")]),
+                    ),
+                ]
+            )"#,
+            &["DP-1"],
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "general.gui.workspaces_per_row: must be at least 1",
+                "binds[1].navigate.forward: invalid key name \"tabb\"",
+                "binds[1].navigate.reverse: reverse modifier is the same as the open modifier (alt)",
+                "binds[1].other.max_switch_offset: must be at most 9 (is 10)",
+                "binds[1].other.monitors[1]: monitor \"DP-9\" doesn't exist (available: DP-1)",
+                "binds[2].other.filter_by[0]: invalid regex \"(unclosed\": regex parse error:\n    (unclosed\n    ^\nerror: unclosed group",
+                "binds[2].open.key: super + tab is already used to open binds[0]",
+            ]
+        );
+    }
+}
@@ -2,14 +2,18 @@ use crate::{CloseType, Exec, ModKey, ReverseKey, SwitchType};
 use serde::{Deserialize, Serialize};
 use smart_default::SmartDefault;
 use std::path::PathBuf;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, SmartDefault, Serialize, Deserialize)]
+#[serde(default)]
 pub struct DispatchConfig {
     pub reverse: bool,
+    #[default = 1]
     pub offset: u8,
 }
 
 #[derive(Debug, Clone, SmartDefault, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SimpleConfig {
     pub ignore_monitors: bool,
     pub ignore_workspaces: bool,
@@ -20,14 +24,203 @@ pub struct SimpleConfig {
     pub include_special_workspaces: bool,
     #[default(SwitchType::Client)]
     pub switch_type: SwitchType,
+    // all filters must match for a window to be shown
+    pub filters: Vec<Filter>,
 }
 
-#[derive(Debug, Clone, Default)]
+/// filter for windows, regexes are stored as string and compiled when collecting the data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Filter {
+    // class / title matches the regex
+    Class(String),
+    Title(String),
+    // class / title doesn't match the regex
+    ExcludeClass(String),
+    ExcludeTitle(String),
+    // only floating (true) or tiled (false) windows
+    Floating(bool),
+    Fullscreen(bool),
+    Pinned(bool),
+    Xwayland(bool),
+}
+
+/// how applications are started from the launcher
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchBackend {
+    // systemd if a systemd user session is running, else fork
+    #[default]
+    Auto,
+    // in a transient systemd user scope (app-hyprswitch-<id>.scope), not stopped with the daemon (forked if systemd-run is not available)
+    Systemd,
+    // forked twice and detached from the daemon, output goes to the journal (with systemd-cat) if available;
+    // stays in the cgroup of the daemon, so it's stopped with the daemon when that runs as a systemd service
+    Fork,
+    // with the exec dispatcher of Hyprland, so exec rules apply and applications open on the workspace selected in the GUI
+    Hyprland,
+}
+
+impl FromStr for LaunchBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "systemd" => Ok(Self::Systemd),
+            "fork" => Ok(Self::Fork),
+            "hyprland" => Ok(Self::Hyprland),
+            _ => Err(format!(
+                "expected auto, systemd, fork or hyprland, found {s:?}"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, SmartDefault)]
 pub struct InitConfig {
     pub custom_css: Option<PathBuf>,
+    #[default = true]
     pub show_title: bool,
+    #[default = 5]
     pub workspaces_per_row: u8,
+    #[default = 6.0]
     pub size_factor: f64,
+    // launcher settings used if not passed to the GUI
+    pub show_launcher: bool,
+    #[default = 5]
+    pub launcher_max_items: usize,
+    #[default = true]
+    pub show_launcher_execs: bool,
+    #[default = 300]
+    pub launcher_animate_launch_time: u64,
+    pub default_terminal: Option<String>,
+    #[default = true]
+    pub remove_html_from_workspace_name: bool,
+    // maximum pixel size of the icons of clients
+    #[default = 512]
+    pub icon_size: u16,
+    // show application-x-executable if no icon was found for a client
+    #[default = true]
+    pub show_default_icon: bool,
+    pub launch_backend: LaunchBackend,
+    // don't show notifications for errors in the daemon
+    pub disable_toasts: bool,
+    // config.ron the settings are loaded from, watched and reloaded by the daemon
+    pub config_file: Option<PathBuf>,
+    // register the binds of the config file with hyprctl keywords
+    pub register_binds: bool,
+    // keywords (bind, submap) generated from the config file, only filled if register_binds is set
+    pub binds: Vec<(String, String)>,
+    // settings from the CLI and env variables, applied over the config file again on reload
+    pub overrides: InitOverrides,
+}
+
+/// settings set with the CLI or env variables, None if not set
+#[derive(Debug, Clone, Default)]
+pub struct InitOverrides {
+    pub custom_css: Option<PathBuf>,
+    pub show_title: Option<bool>,
+    pub workspaces_per_row: Option<u8>,
+    pub size_factor: Option<f64>,
+    pub show_launcher: Option<bool>,
+    pub launcher_max_items: Option<usize>,
+    pub show_launcher_execs: Option<bool>,
+    pub launcher_animate_launch_time: Option<u64>,
+    pub default_terminal: Option<String>,
+    pub remove_html_from_workspace_name: Option<bool>,
+    pub icon_size: Option<u16>,
+    pub show_default_icon: Option<bool>,
+    pub launch_backend: Option<LaunchBackend>,
+    pub disable_toasts: Option<bool>,
+}
+
+impl InitOverrides {
+    /// values of `self` take precedence, unset values are taken from `other`
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            custom_css: self.custom_css.or(other.custom_css),
+            show_title: self.show_title.or(other.show_title),
+            workspaces_per_row: self.workspaces_per_row.or(other.workspaces_per_row),
+            size_factor: self.size_factor.or(other.size_factor),
+            show_launcher: self.show_launcher.or(other.show_launcher),
+            launcher_max_items: self.launcher_max_items.or(other.launcher_max_items),
+            show_launcher_execs: self.show_launcher_execs.or(other.show_launcher_execs),
+            launcher_animate_launch_time: self
+                .launcher_animate_launch_time
+                .or(other.launcher_animate_launch_time),
+            default_terminal: self.default_terminal.or(other.default_terminal),
+            remove_html_from_workspace_name: self
+                .remove_html_from_workspace_name
+                .or(other.remove_html_from_workspace_name),
+            icon_size: self.icon_size.or(other.icon_size),
+            show_default_icon: self.show_default_icon.or(other.show_default_icon),
+            launch_backend: self.launch_backend.or(other.launch_backend),
+            disable_toasts: self.disable_toasts.or(other.disable_toasts),
+        }
+    }
+}
+
+impl InitConfig {
+    /// replaces the settings (from the defaults or config file) with the overrides that are set
+    pub fn apply_overrides(self) -> Self {
+        let o = self.overrides.clone();
+        Self {
+            custom_css: o.custom_css.or(self.custom_css),
+            show_title: o.show_title.unwrap_or(self.show_title),
+            workspaces_per_row: o.workspaces_per_row.unwrap_or(self.workspaces_per_row),
+            size_factor: o.size_factor.unwrap_or(self.size_factor),
+            show_launcher: o.show_launcher.unwrap_or(self.show_launcher),
+            launcher_max_items: o.launcher_max_items.unwrap_or(self.launcher_max_items),
+            show_launcher_execs: o.show_launcher_execs.unwrap_or(self.show_launcher_execs),
+            launcher_animate_launch_time: o
+                .launcher_animate_launch_time
+                .unwrap_or(self.launcher_animate_launch_time),
+            default_terminal: o.default_terminal.or(self.default_terminal),
+            remove_html_from_workspace_name: o
+                .remove_html_from_workspace_name
+                .unwrap_or(self.remove_html_from_workspace_name),
+            icon_size: o.icon_size.unwrap_or(self.icon_size),
+            show_default_icon: o.show_default_icon.unwrap_or(self.show_default_icon),
+            launch_backend: o.launch_backend.unwrap_or(self.launch_backend),
+            disable_toasts: o.disable_toasts.unwrap_or(self.disable_toasts),
+            ..self
+        }
+    }
+
+    /// checks the ranges of the settings, all errors are reported together
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut errors = Vec::new();
+        if self.workspaces_per_row == 0 {
+            errors.push("workspaces_per_row must be at least 1".to_string());
+        }
+        if !(self.size_factor.is_finite() && self.size_factor > 0.0) {
+            errors.push(format!(
+                "size_factor must be greater than 0 (is {})",
+                self.size_factor
+            ));
+        }
+        if !(1..=10).contains(&self.launcher_max_items) {
+            errors.push(format!(
+                "launcher_max_items must be between 1 and 10 (is {})",
+                self.launcher_max_items
+            ));
+        }
+        if self.icon_size == 0 {
+            errors.push("icon_size must be at least 1".to_string());
+        }
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "Found {} invalid settings:\n{}",
+                errors.len(),
+                errors
+                    .iter()
+                    .map(|e| format!("- {e}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, SmartDefault, Serialize, Deserialize)]
@@ -47,12 +240,17 @@ pub enum SubmapConfig {
 }
 
 #[derive(Debug, Clone, SmartDefault, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GuiConfig {
     pub max_switch_offset: u8,
     pub hide_active_window_border: bool,
     pub monitors: Option<Vec<String>>,
     pub show_workspaces_on_all_monitors: bool,
-    pub show_launcher: bool,
+    // the launcher settings of the daemon are used if None
+    pub show_launcher: Option<bool>,
+    pub launcher_max_items: Option<usize>,
+    pub show_launcher_execs: Option<bool>,
+    pub default_terminal: Option<String>,
 }
 
 #[derive(Debug, Default)]
@@ -60,6 +258,45 @@ pub struct LauncherConfig {
     pub execs: Vec<Exec>,
     pub selected: Option<usize>,
     pub launch_state: LaunchState,
+    // settings of the current GUI (from the GuiConfig or the InitConfig)
+    pub show: bool,
+    pub max_items: usize,
+    pub show_execs: bool,
+    pub default_terminal: Option<String>,
+}
+
+impl LauncherConfig {
+    /// launcher settings of a GUI, the settings of the daemon are used for options not passed to the GUI
+    pub fn new(
+        gui_config: &GuiConfig,
+        init_config: &InitConfig,
+        submap_config: &SubmapConfig,
+    ) -> Self {
+        // the launcher can't be used when closing on release of the mod key
+        let close_on_release = matches!(
+            submap_config,
+            SubmapConfig::Config {
+                close: CloseType::ModKeyRelease,
+                ..
+            }
+        );
+        Self {
+            show: gui_config
+                .show_launcher
+                .unwrap_or(init_config.show_launcher && !close_on_release),
+            max_items: gui_config
+                .launcher_max_items
+                .unwrap_or(init_config.launcher_max_items),
+            show_execs: gui_config
+                .show_launcher_execs
+                .unwrap_or(init_config.show_launcher_execs),
+            default_terminal: gui_config
+                .default_terminal
+                .clone()
+                .or_else(|| init_config.default_terminal.clone()),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -0,0 +1,146 @@
+use crate::Warn;
+use anyhow::Context;
+use hyprland::data::{Bind, Binds};
+use hyprland::keyword::Keyword;
+use hyprland::prelude::{HyprData, HyprDataVec};
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, info, span, trace, warn, Level};
+
+// modifiers of the modmask returned by hyprctl binds
+const MODS: [(u16, &str); 8] = [
+    (1, "SHIFT"),
+    (2, "CAPS"),
+    (4, "CTRL"),
+    (8, "ALT"),
+    (16, "MOD2"),
+    (32, "MOD3"),
+    (64, "SUPER"),
+    (128, "MOD5"),
+];
+
+/// binds added by [`register_binds`] (as returned by hyprctl binds)
+fn get_registered() -> &'static Mutex<Vec<Bind>> {
+    static REGISTERED: OnceLock<Mutex<Vec<Bind>>> = OnceLock::new();
+    REGISTERED.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// registers the keywords (bind, submap) generated from the config, previously registered binds are removed first
+pub(super) fn register_binds(keywords: &[(String, String)]) -> anyhow::Result<()> {
+    let _span = span!(Level::TRACE, "binds").entered();
+    unregister_binds();
+
+    let before = Binds::get().context("Failed to get binds")?.to_vec();
+    let result = (|| -> anyhow::Result<()> {
+        for (keyword, value) in keywords {
+            trace!("{} = {}", keyword, value);
+            Keyword::set(keyword, value.clone())
+                .with_context(|| format!("Failed to set {keyword} = {value}"))?;
+        }
+        Ok(())
+    })();
+    // following binds would be added to the submap if a keyword failed
+    Keyword::set("submap", "reset").warn("Failed to reset submap");
+
+    // remember the added binds even if not all could be registered
+    let after = Binds::get().context("Failed to get binds")?.to_vec();
+    let added = difference(after, &before);
+    info!("Registered {} binds", added.len());
+    *get_registered().lock().expect("Failed to lock") = added;
+    result
+}
+
+/// removes all binds added by [`register_binds`]
+pub fn unregister_binds() {
+    let _span = span!(Level::TRACE, "binds").entered();
+    let mut registered = get_registered().lock().expect("Failed to lock");
+    if registered.is_empty() {
+        return;
+    }
+
+    // hyprland removes binds with the same keys from all submaps, so the binds of others are added again
+    let restore = match Binds::get() {
+        Ok(binds) => difference(binds.to_vec(), &registered)
+            .into_iter()
+            .filter(|b| registered.iter().any(|r| same_keys(r, b)))
+            .collect(),
+        Err(e) => {
+            warn!("Failed to get binds, unable to restore overlapping binds: {e:?}");
+            Vec::new()
+        }
+    };
+
+    let mut unbinds = registered.iter().map(keys).collect::<Vec<_>>();
+    unbinds.sort();
+    unbinds.dedup();
+    for unbind in unbinds {
+        trace!("unbind = {}", unbind);
+        Keyword::set("unbind", unbind).warn("Failed to unbind");
+    }
+    for bind in restore {
+        debug!("Restoring overlapping bind {bind:?}");
+        if !bind.submap.is_empty() {
+            Keyword::set("submap", bind.submap.clone()).warn("Failed to set submap");
+        }
+        Keyword::set(
+            bind_keyword(&bind),
+            format!("{}, {}, {}", keys(&bind), bind.dispatcher, bind.arg),
+        )
+        .warn("Failed to restore bind");
+        if !bind.submap.is_empty() {
+            Keyword::set("submap", "reset").warn("Failed to reset submap");
+        }
+    }
+    info!("Removed {} binds", registered.len());
+    registered.clear();
+}
+
+/// all binds in `binds` that aren't in `remove` (every bind in `remove` only removes one bind)
+fn difference(binds: Vec<Bind>, remove: &[Bind]) -> Vec<Bind> {
+    let mut remove = remove.to_vec();
+    binds
+        .into_iter()
+        .filter(|bind| match remove.iter().position(|r| r == bind) {
+            Some(index) => {
+                remove.swap_remove(index);
+                false
+            }
+            None => true,
+        })
+        .collect()
+}
+
+fn same_keys(a: &Bind, b: &Bind) -> bool {
+    a.modmask == b.modmask && a.key == b.key && a.keycode == b.keycode
+}
+
+/// `MODS, key` like in the Hyprland config
+fn keys(bind: &Bind) -> String {
+    let mods = MODS
+        .iter()
+        .filter(|(mask, _)| bind.modmask & mask != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(" ");
+    if bind.key.is_empty() {
+        format!("{mods}, code:{}", bind.keycode)
+    } else {
+        format!("{mods}, {}", bind.key)
+    }
+}
+
+fn bind_keyword(bind: &Bind) -> String {
+    let mut keyword = "bind".to_string();
+    if bind.locked {
+        keyword.push('l');
+    }
+    if bind.release {
+        keyword.push('r');
+    }
+    if bind.repeat {
+        keyword.push('e');
+    }
+    if bind.mouse {
+        keyword.push('m');
+    }
+    keyword
+}
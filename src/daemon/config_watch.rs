@@ -0,0 +1,49 @@
+use crate::daemon::handle_fns::reload;
+use crate::daemon::ordered::Ticket;
+use crate::{global, toast, Share};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tracing::{info, trace, warn};
+
+// how often the modification time of the config file is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// reloads the config when the file is modified (changes are applied after the GUI was closed)
+pub(super) fn start_config_watcher_blocking(share: &Share, path: &Path) {
+    info!("Watching config file {path:?}");
+    let mut applied = modified(path);
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = modified(path);
+        if current == applied {
+            continue;
+        }
+
+        // ordered with the client transitions, so the GUI can't be opened while reloading
+        let reloaded = Ticket::new().run(|| {
+            if *global::OPEN
+                .get()
+                .expect("ACTIVE not set")
+                .lock()
+                .expect("Failed to lock ACTIVE")
+            {
+                trace!("GUI is open, delaying reload");
+                return false;
+            }
+            info!("Config file {path:?} changed, reloading");
+            if let Err(e) = reload(share) {
+                warn!("{:?}", e);
+                toast(&format!("Failed to reload config: {e:#}"));
+            }
+            true
+        });
+        if reloaded {
+            applied = current;
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    path.metadata().and_then(|m| m.modified()).ok()
+}
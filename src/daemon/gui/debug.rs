@@ -1,6 +1,8 @@
 use super::maps::{get_desktop_files_debug, get_icon_name_debug};
 use crate::daemon::gui::check_themes;
+use crate::daemon::history::{load_history, now};
 use anyhow::Context;
+use std::collections::HashSet;
 
 pub fn debug_search_class(class: String) -> anyhow::Result<()> {
     if class.is_empty() {
@@ -69,14 +71,58 @@ pub fn debug_list() -> anyhow::Result<()> {
 
 pub fn debug_desktop_files() -> anyhow::Result<()> {
     let map = get_desktop_files_debug()?;
-    for (name, icon, _, exec, _, _, file) in map {
+    for file in map {
         #[allow(clippy::print_stdout)]
         {
             println!(
                 "Desktop file: {} [{:?}] -> {:?} [{:?}]",
-                name, file, exec, icon
+                file.name, file.file, file.exec, file.icon
             );
+            for action in file.actions {
+                println!(
+                    "    Action: {} ({}) -> {:?} [{:?}]",
+                    action.name, action.id, action.exec, action.icon
+                );
+            }
         }
     }
     Ok(())
 }
+
+pub fn debug_history() -> anyhow::Result<()> {
+    let history = load_history()?;
+    let desktop_files = get_desktop_files_debug()?;
+    let installed = desktop_files.iter().map(|f| &*f.id).collect::<HashSet<_>>();
+    let now = now();
+    let mut entries = history.entries.iter().collect::<Vec<_>>();
+    entries.sort_by(|(_, a), (_, b)| b.frecency(now).total_cmp(&a.frecency(now)));
+    for (id, entry) in &entries {
+        #[allow(clippy::print_stdout)]
+        {
+            println!(
+                "{id}: frecency {:.2}, {} launches, last launch {} ago{}",
+                entry.frecency(now),
+                entry.launches,
+                format_age(now.saturating_sub(entry.last_launch)),
+                if installed.contains(id.as_str()) {
+                    ""
+                } else {
+                    " (not installed)"
+                }
+            );
+        }
+    }
+    #[allow(clippy::print_stdout)]
+    {
+        println!("{} applications in history", entries.len());
+    }
+    Ok(())
+}
+
+fn format_age(secs: u64) -> String {
+    match secs {
+        s if s < 60 * 60 => format!("{}m", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h", s / (60 * 60)),
+        s => format!("{}d", s / (24 * 60 * 60)),
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::bail;
+use std::path::Path;
+
+// characters that have to be inside a quoted argument
+const RESERVED: [char; 17] = [
+    '\n', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')', '`', '\r',
+];
+
+/// splits an `Exec` value into the program and its arguments and expands the field codes
+///
+/// `%c` is replaced with the `name`, `%k` with the desktop `file` and `%i` with `--icon <icon>`,
+/// file and URL codes are removed as nothing is opened with the application.
+/// Values that don't follow the quoting rules of the spec (e.g. using `~` or `$VAR`) are errors, these have to be run with a shell
+pub fn parse_exec(
+    exec: &str,
+    name: &str,
+    icon: Option<&str>,
+    file: &Path,
+) -> anyhow::Result<Vec<String>> {
+    let mut args = Vec::new();
+    for (arg, quoted) in tokenize(exec)? {
+        // field codes that are a whole argument, quoted arguments are never field codes
+        if !quoted {
+            match arg.as_str() {
+                "%f" | "%F" | "%u" | "%U" | "%d" | "%D" | "%n" | "%N" | "%v" | "%m" => continue,
+                "%i" => {
+                    if let Some(icon) = icon {
+                        args.push("--icon".to_string());
+                        args.push(icon.to_string());
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        args.push(expand(&arg, name, file));
+    }
+    if args.is_empty() {
+        bail!("Exec is empty");
+    }
+    Ok(args)
+}
+
+/// (argument, whether it was quoted) of the space separated arguments
+fn tokenize(exec: &str) -> anyhow::Result<Vec<(String, bool)>> {
+    let mut args = Vec::new();
+    let mut current: Option<(String, bool)> = None;
+    let mut chars = exec.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            ' ' | '\t' => args.extend(current.take()),
+            '"' => {
+                let (arg, quoted) = current.get_or_insert_with(|| (String::new(), false));
+                *quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(char @ ('"' | '`' | '$' | '\\')) => arg.push(char),
+                            Some(char) => bail!("Invalid escape sequence \\{char} in quotes"),
+                            None => bail!("Unterminated quote"),
+                        },
+                        Some(char) => arg.push(char),
+                        None => bail!("Unterminated quote"),
+                    }
+                }
+            }
+            char if RESERVED.contains(&char) => {
+                bail!("Reserved character {char:?} outside of quotes")
+            }
+            char => current
+                .get_or_insert_with(|| (String::new(), false))
+                .0
+                .push(char),
+        }
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+/// expands `%c`, `%k` and `%%` inside an argument, other field codes are removed
+fn expand(arg: &str, name: &str, file: &Path) -> String {
+    let mut res = String::with_capacity(arg.len());
+    let mut chars = arg.chars();
+    while let Some(char) = chars.next() {
+        if char != '%' {
+            res.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => res.push('%'),
+            Some('c') => res.push_str(name),
+            Some('k') => res.push_str(&file.to_string_lossy()),
+            _ => {}
+        }
+    }
+    res
+}
+
+/// removes the field codes (`%f`, `%U`, ...) from an `Exec` value, `%%` is replaced with `%`
+pub fn strip_field_codes(exec: &str) -> String {
+    let mut res = String::with_capacity(exec.len());
+    let mut chars = exec.chars();
+    while let Some(char) = chars.next() {
+        if char != '%' {
+            res.push(char);
+            continue;
+        }
+        // f, F, u, U, i, c, k and the deprecated d, D, n, N, v, m (invalid codes are removed too)
+        if chars.next() == Some('%') {
+            res.push('%');
+        }
+    }
+    res.trim().to_string()
+}
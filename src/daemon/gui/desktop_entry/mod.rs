@@ -0,0 +1,291 @@
+use crate::handle::find_executable;
+use anyhow::bail;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+pub use exec::{parse_exec, strip_field_codes};
+
+mod exec;
+#[cfg(test)]
+mod tests;
+
+const MAIN_GROUP: &str = "Desktop Entry";
+
+/// parsed desktop entry file (https://specifications.freedesktop.org/desktop-entry-spec/latest/)
+#[derive(Debug)]
+pub struct DesktopEntry {
+    groups: HashMap<String, Group>,
+}
+
+/// keys of a group like `[Desktop Entry]`, localized keys contain the locale (`Name[de]`)
+#[derive(Debug, Default)]
+pub struct Group {
+    entries: HashMap<String, String>,
+}
+
+impl DesktopEntry {
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let mut groups = HashMap::<String, Group>::new();
+        let mut current = None;
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if groups.contains_key(name) {
+                    bail!("Line {}: duplicate group [{name}]", i + 1);
+                }
+                groups.insert(name.to_string(), Group::default());
+                current = Some(name.to_string());
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("Line {}: expected key=value, found {line:?}", i + 1);
+            };
+            let Some(group) = current.as_ref().and_then(|g| groups.get_mut(g)) else {
+                bail!("Line {}: key {key:?} is outside of a group", i + 1);
+            };
+            // keys must not be duplicated, use the first one like most implementations
+            group
+                .entries
+                .entry(key.trim().to_string())
+                .or_insert_with(|| value.trim().to_string());
+        }
+        if !groups.contains_key(MAIN_GROUP) {
+            bail!("Missing [{MAIN_GROUP}] group");
+        }
+        Ok(Self { groups })
+    }
+
+    /// the `[Desktop Entry]` group
+    pub fn main(&self) -> &Group {
+        &self.groups[MAIN_GROUP]
+    }
+
+    /// (action ID, `[Desktop Action <ID>]` group) of the actions in `Actions`, missing groups are skipped
+    pub fn actions(&self) -> Vec<(String, &Group)> {
+        self.main()
+            .strings("Actions")
+            .into_iter()
+            .filter_map(|id| {
+                let group = self.groups.get(&format!("Desktop Action {id}"))?;
+                Some((id, group))
+            })
+            .collect()
+    }
+
+    /// whether the entry is an application that should be shown in the launcher
+    ///
+    /// checks `Type`, `Hidden`, `NoDisplay`, `OnlyShowIn`/`NotShowIn` against the `desktops` and `TryExec`
+    pub fn is_shown(&self, desktops: &[String]) -> bool {
+        let main = self.main();
+        main.raw("Type") == Some("Application")
+            && !main.bool("Hidden")
+            && !main.bool("NoDisplay")
+            && shown_in(main, desktops)
+            && main
+                .string("TryExec")
+                .is_none_or(|try_exec| find_executable(&try_exec, env::var_os("PATH").as_deref()))
+    }
+}
+
+impl Group {
+    /// value without replaced escape sequences
+    pub fn raw(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    pub fn string(&self, key: &str) -> Option<String> {
+        self.raw(key).map(unescape)
+    }
+
+    /// value of the first of the `locales` with a translation, else the untranslated value
+    pub fn locale_string(&self, key: &str, locales: &[String]) -> Option<String> {
+        locales
+            .iter()
+            .find_map(|locale| self.raw(&format!("{key}[{locale}]")))
+            .or_else(|| self.raw(key))
+            .map(unescape)
+    }
+
+    pub fn bool(&self, key: &str) -> bool {
+        self.raw(key) == Some("true")
+    }
+
+    /// values of a `;` separated list
+    pub fn strings(&self, key: &str) -> Vec<String> {
+        self.raw(key).map(split_list).unwrap_or_default()
+    }
+
+    pub fn locale_strings(&self, key: &str, locales: &[String]) -> Vec<String> {
+        locales
+            .iter()
+            .find_map(|locale| self.raw(&format!("{key}[{locale}]")))
+            .or_else(|| self.raw(key))
+            .map(split_list)
+            .unwrap_or_default()
+    }
+}
+
+/// replaces `\s`, `\n`, `\t`, `\r` and `\\`
+fn unescape(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            res.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => res.push(' '),
+            Some('n') => res.push('\n'),
+            Some('t') => res.push('\t'),
+            Some('r') => res.push('\r'),
+            Some('\\') => res.push('\\'),
+            Some(other) => {
+                res.push('\\');
+                res.push(other);
+            }
+            None => res.push('\\'),
+        }
+    }
+    res
+}
+
+/// splits at `;` that aren't escaped with `\;`, empty values are skipped
+fn split_list(value: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some(';') => current.push(';'),
+                Some(other) => {
+                    current.push('\\');
+                    current.push(other);
+                }
+                None => current.push('\\'),
+            },
+            ';' => values.push(std::mem::take(&mut current)),
+            _ => current.push(char),
+        }
+    }
+    values.push(current);
+    values
+        .iter()
+        .map(|v| unescape(v.trim()))
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn shown_in(group: &Group, desktops: &[String]) -> bool {
+    let only_show_in = group.strings("OnlyShowIn");
+    if !only_show_in.is_empty() && !only_show_in.iter().any(|d| desktops.contains(d)) {
+        return false;
+    }
+    !group
+        .strings("NotShowIn")
+        .iter()
+        .any(|d| desktops.contains(d))
+}
+
+/// locales to look up localized keys in order of preference, from `LC_ALL`, `LC_MESSAGES` or `LANG`
+pub fn get_locales() -> Vec<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|var| env::var(var).ok().filter(|l| !l.is_empty()))
+        .map(|locale| locale_variants(&locale))
+        .unwrap_or_default()
+}
+
+/// `lang_COUNTRY@MODIFIER`, `lang_COUNTRY`, `lang@MODIFIER`, `lang` of a locale like `de_DE.UTF-8@euro`
+fn locale_variants(locale: &str) -> Vec<String> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+    // the encoding is ignored
+    let locale = locale.split('.').next().unwrap_or(locale);
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return Vec::new();
+    }
+    let (lang, country) = match locale.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (locale, None),
+    };
+
+    let mut variants = Vec::new();
+    if let Some(country) = country {
+        if let Some(modifier) = modifier {
+            variants.push(format!("{lang}_{country}@{modifier}"));
+        }
+        variants.push(format!("{lang}_{country}"));
+    }
+    if let Some(modifier) = modifier {
+        variants.push(format!("{lang}@{modifier}"));
+    }
+    variants.push(lang.to_string());
+    variants
+}
+
+/// desktops from `XDG_CURRENT_DESKTOP` (e.g. `Hyprland`) to check `OnlyShowIn` and `NotShowIn`
+pub fn get_current_desktops() -> Vec<String> {
+    env::var("XDG_CURRENT_DESKTOP")
+        .map(|d| {
+            d.split(':')
+                .filter(|d| !d.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// (desktop file ID, path) of all desktop files in the `applications` dirs
+///
+/// dirs are ordered by priority, if multiple dirs contain the same ID (like a user override in `XDG_DATA_HOME`) only the first is used
+pub fn collect_desktop_files(dirs: &[PathBuf]) -> Vec<(Box<str>, PathBuf)> {
+    let mut ids = HashSet::new();
+    let mut res = Vec::new();
+    for dir in dirs {
+        if !dir.exists() {
+            continue;
+        }
+        let mut files = Vec::new();
+        collect_dir(dir, dir, &mut files);
+        // read_dir has no defined order
+        files.sort();
+        for (id, path) in files {
+            if ids.insert(id.clone()) {
+                res.push((id, path));
+            }
+        }
+    }
+    debug!("found {} desktop files", res.len());
+    res
+}
+
+/// subdirectories are part of the ID (`applications/kde/konsole.desktop` has the ID `kde-konsole.desktop`)
+fn collect_dir(root: &Path, dir: &Path, res: &mut Vec<(Box<str>, PathBuf)>) {
+    let entries = match dir.read_dir() {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Failed to read dir {dir:?}: {e}");
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_dir(root, &path, res);
+        } else if path.is_file() && path.extension().is_some_and(|e| e == "desktop") {
+            if let Ok(relative) = path.strip_prefix(root) {
+                let id = relative.to_string_lossy().replace('/', "-");
+                res.push((id.into(), path));
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use super::*;
+use std::ffi::OsStr;
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/daemon/gui/desktop_entry/tests/fixtures")
+}
+
+fn parse(path: &str) -> DesktopEntry {
+    let content = std::fs::read_to_string(fixtures().join(path)).expect("Failed to read fixture");
+    DesktopEntry::parse(&content).expect("Failed to parse fixture")
+}
+
+fn locales(locales: &[&str]) -> Vec<String> {
+    locales.iter().map(|l| l.to_string()).collect()
+}
+
+#[test]
+fn actions_dont_override_main_group() {
+    let entry = parse("data_dir/applications/firefox.desktop");
+    let main = entry.main();
+    assert_eq!(main.string("Name").as_deref(), Some("Firefox"));
+    assert_eq!(
+        main.string("Exec").as_deref(),
+        Some("/usr/lib/firefox/firefox %u")
+    );
+    assert_eq!(main.string("Icon").as_deref(), Some("firefox"));
+    assert_eq!(
+        main.strings("Actions"),
+        vec!["new-window", "new-private-window"]
+    );
+}
+
+#[test]
+fn actions() {
+    let entry = parse("data_dir/applications/firefox.desktop");
+    let actions = entry.actions();
+    let ids = actions
+        .iter()
+        .map(|(id, _)| id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["new-window", "new-private-window"]);
+
+    let (_, new_window) = &actions[0];
+    assert_eq!(
+        new_window
+            .locale_string("Name", &locales(&["de"]))
+            .as_deref(),
+        Some("Neues Fenster")
+    );
+    assert_eq!(new_window.string("Icon"), None);
+    let (_, private_window) = &actions[1];
+    assert_eq!(
+        private_window.string("Exec").as_deref(),
+        Some("/usr/lib/firefox/firefox --private-window %u")
+    );
+    assert_eq!(
+        private_window.string("Icon").as_deref(),
+        Some("firefox-private")
+    );
+
+    // no Actions key
+    assert!(parse("data_dir/applications/htop.desktop")
+        .actions()
+        .is_empty());
+}
+
+#[test]
+fn localized_keys() {
+    let entry = parse("data_dir/applications/firefox.desktop");
+    let main = entry.main();
+    let de_at = locales(&["de_AT", "de"]);
+    assert_eq!(
+        main.locale_string("Name", &de_at).as_deref(),
+        Some("Firefox Webbrowser")
+    );
+    assert_eq!(
+        main.locale_string("Comment", &de_at).as_deref(),
+        Some("Im Internet surfen")
+    );
+    assert_eq!(
+        main.locale_string("Comment", &locales(&["de_DE", "de"]))
+            .as_deref(),
+        Some("Browse the World Wide Web")
+    );
+    assert_eq!(
+        main.locale_string("Name", &locales(&["fr"])).as_deref(),
+        Some("Firefox")
+    );
+    assert_eq!(
+        main.locale_strings("Keywords", &de_at),
+        vec!["Internet", "WWW", "Browser", "Web;Surfen"]
+    );
+}
+
+#[test]
+fn locale_variants_by_preference() {
+    assert_eq!(
+        locale_variants("de_DE.UTF-8@euro"),
+        vec!["de_DE@euro", "de_DE", "de@euro", "de"]
+    );
+    assert_eq!(locale_variants("en_US.UTF-8"), vec!["en_US", "en"]);
+    assert_eq!(locale_variants("sr@latin"), vec!["sr@latin", "sr"]);
+    assert_eq!(locale_variants("C.UTF-8"), Vec::<String>::new());
+}
+
+#[test]
+fn escape_sequences() {
+    let entry = parse("data_dir/applications/htop.desktop");
+    let main = entry.main();
+    assert_eq!(
+        main.string("Comment").as_deref(),
+        Some("Show System Processes\\Monitor")
+    );
+    assert_eq!(
+        main.raw("Comment"),
+        Some("Show\\sSystem Processes\\\\Monitor")
+    );
+    assert!(main.bool("Terminal"));
+    assert_eq!(main.string("Path").as_deref(), Some("/tmp"));
+}
+
+#[test]
+fn field_codes() {
+    assert_eq!(
+        strip_field_codes("/usr/lib/firefox/firefox %u"),
+        "/usr/lib/firefox/firefox"
+    );
+    assert_eq!(
+        strip_field_codes("htop --sort-key=PERCENT_CPU %i %c %k"),
+        "htop --sort-key=PERCENT_CPU"
+    );
+    assert_eq!(strip_field_codes("echo 100%% %F"), "echo 100%");
+}
+
+#[test]
+fn exec_arguments() {
+    let file = Path::new("/usr/share/applications/app.desktop");
+    let args = |exec: &str| parse_exec(exec, "App", Some("app-icon"), file);
+    assert_eq!(
+        args("/usr/lib/firefox/firefox %u").unwrap(),
+        vec!["/usr/lib/firefox/firefox"]
+    );
+    assert_eq!(
+        args("app --name=%c --desktop-file %k %i %F").unwrap(),
+        vec![
+            "app",
+            "--name=App",
+            "--desktop-file",
+            "/usr/share/applications/app.desktop",
+            "--icon",
+            "app-icon",
+        ]
+    );
+    assert_eq!(
+        parse_exec("app %i", "App", None, file).unwrap(),
+        vec!["app"]
+    );
+    assert_eq!(args("printf 100%%").unwrap(), vec!["printf", "100%"]);
+    assert_eq!(
+        args(r#"sh -c "echo \"quoted \$HOME\" \\ \`pwd\`"  "" last"#).unwrap(),
+        vec!["sh", "-c", r#"echo "quoted $HOME" \ `pwd`"#, "", "last"]
+    );
+}
+
+#[test]
+fn exec_arguments_from_file() {
+    let path = fixtures().join("data_dir/applications/htop.desktop");
+    let entry = parse("data_dir/applications/htop.desktop");
+    let main = entry.main();
+    let exec = main.string("Exec").unwrap();
+    let args = parse_exec(&exec, "Htop", main.string("Icon").as_deref(), &path).unwrap();
+    assert_eq!(
+        args,
+        vec![
+            "htop".to_string(),
+            "--sort-key=PERCENT_CPU".to_string(),
+            "--icon".to_string(),
+            "htop".to_string(),
+            "Htop".to_string(),
+            path.to_string_lossy().to_string(),
+        ]
+    );
+}
+
+#[test]
+fn legacy_exec() {
+    let file = Path::new("/usr/share/applications/app.desktop");
+    let error = |exec: &str| {
+        parse_exec(exec, "App", None, file)
+            .expect_err("Invalid Exec was parsed")
+            .to_string()
+    };
+    assert_eq!(
+        error("app ~/file"),
+        "Reserved character '~' outside of quotes"
+    );
+    assert_eq!(
+        error("app $HOME"),
+        "Reserved character '$' outside of quotes"
+    );
+    assert_eq!(error("a | b"), "Reserved character '|' outside of quotes");
+    assert_eq!(error(r#"sh -c "echo"#), "Unterminated quote");
+    assert_eq!(
+        error(r#"sh -c "\n""#),
+        "Invalid escape sequence \\n in quotes"
+    );
+    assert_eq!(error(" %f "), "Exec is empty");
+}
+
+#[test]
+fn shown_applications() {
+    let hyprland = locales(&["Hyprland"]);
+    assert!(parse("data_dir/applications/firefox.desktop").is_shown(&hyprland));
+    assert!(parse("data_dir/applications/htop.desktop").is_shown(&hyprland));
+
+    // OnlyShowIn=KDE
+    let konsole = parse("data_dir/applications/kde/konsole.desktop");
+    assert!(!konsole.is_shown(&hyprland));
+    assert!(konsole.is_shown(&locales(&["KDE"])));
+
+    // TryExec isn't installed
+    assert!(!parse("data_dir/applications/missing.desktop").is_shown(&hyprland));
+    // Hidden=true
+    assert!(!parse("data_home/applications/removed.desktop").is_shown(&hyprland));
+}
+
+#[test]
+fn executables() {
+    let path = OsStr::new("/nonexistent:/bin:/usr/bin");
+    assert!(find_executable("sh", Some(path)));
+    assert!(find_executable("/bin/sh", None));
+    assert!(!find_executable("sh", None));
+    assert!(!find_executable(
+        "hyprswitch-test-missing-binary",
+        Some(path)
+    ));
+    // not executable
+    let file = fixtures().join("data_dir/applications/readme.txt");
+    assert!(!find_executable(&file.to_string_lossy(), Some(path)));
+}
+
+#[test]
+fn invalid_files() {
+    let content = std::fs::read_to_string(fixtures().join("data_dir/applications/invalid.desktop"))
+        .expect("Failed to read fixture");
+    let error = DesktopEntry::parse(&content).expect_err("Invalid file was parsed");
+    assert_eq!(
+        error.to_string(),
+        "Line 1: key \"Name\" is outside of a group"
+    );
+
+    let error = DesktopEntry::parse("[Desktop Action new]\nName=New")
+        .expect_err("File without main group was parsed");
+    assert_eq!(error.to_string(), "Missing [Desktop Entry] group");
+
+    let error =
+        DesktopEntry::parse("[Desktop Entry]\nName").expect_err("Line without value was parsed");
+    assert_eq!(
+        error.to_string(),
+        "Line 2: expected key=value, found \"Name\""
+    );
+}
+
+#[test]
+fn user_overrides() {
+    let dirs = [
+        fixtures().join("data_home/applications"),
+        fixtures().join("data_dir/applications"),
+        fixtures().join("nonexistent/applications"),
+    ];
+    let files = collect_desktop_files(&dirs);
+    let ids = files.iter().map(|(id, _)| &**id).collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        vec![
+            "firefox.desktop",
+            "removed.desktop",
+            "htop.desktop",
+            "invalid.desktop",
+            "kde-konsole.desktop",
+            "missing.desktop",
+        ]
+    );
+    // the desktop files in XDG_DATA_HOME are used
+    assert_eq!(files[0].1, dirs[0].join("firefox.desktop"));
+    assert_eq!(files[1].1, dirs[0].join("removed.desktop"));
+    assert_eq!(files[4].1, dirs[1].join("kde/konsole.desktop"));
+}
@@ -1,9 +1,10 @@
-use crate::daemon::cache::cache_run;
 use crate::daemon::deactivate_submap;
 use crate::daemon::gui::launcher::show_launch_spawn;
 use crate::daemon::gui::reload_desktop_maps;
-use crate::handle::{clear_recent_clients, run_program, switch_to_active};
-use crate::{global, Active, GUISend, Share, UpdateCause, Warn};
+use crate::daemon::history::record_launch;
+use crate::daemon::subscribe::emit;
+use crate::handle::{run_program, selected_workspace, switch_to_active};
+use crate::{global, Active, Event, GUISend, Share, UpdateCause, Warn};
 use anyhow::Context;
 use gtk4::glib::clone;
 use hyprland::shared::{Address, MonitorId, WorkspaceId};
@@ -12,30 +13,29 @@ use std::thread;
 use tracing::{trace, warn};
 
 pub(crate) fn gui_set_client(share: &Share, address: Address) {
-    let (latest, _, _) = share.deref();
-    {
-        let mut lock = latest.lock().expect("Failed to lock");
-        lock.active = Some(Active::Client(address));
-        drop(lock);
-    }
+    set_active(share, Active::Client(address));
 }
 
 pub(crate) fn gui_set_workspace(share: &Share, id: WorkspaceId) {
-    let (latest, _, _) = share.deref();
-    {
-        let mut lock = latest.lock().expect("Failed to lock");
-        lock.active = Some(Active::Workspace(id));
-        drop(lock);
-    }
+    set_active(share, Active::Workspace(id));
 }
 
 pub(crate) fn gui_set_monitor(share: &Share, id: MonitorId) {
+    set_active(share, Active::Monitor(id));
+}
+
+/// selection changed by clicking in the GUI
+fn set_active(share: &Share, active: Active) {
     let (latest, _, _) = share.deref();
-    {
+    let event = {
         let mut lock = latest.lock().expect("Failed to lock");
-        lock.active = Some(Active::Monitor(id));
-        drop(lock);
-    }
+        lock.active = Some(active);
+        Event::SelectionChanged {
+            selected: lock.active.clone(),
+            launcher_selected: lock.launcher_config.selected,
+        }
+    };
+    emit(event);
 }
 
 pub(crate) fn gui_change_entry_input(share: &Share) {
@@ -75,6 +75,10 @@ pub(crate) fn gui_change_selected_program(share: &Share, reverse: bool) {
                 } else {
                     return;
                 };
+                emit(Event::SelectionChanged {
+                    selected: lock.active.clone(),
+                    launcher_selected: lock.launcher_config.selected,
+                });
                 drop(lock);
             }
 
@@ -112,10 +116,12 @@ pub(crate) fn gui_close(share: &Share) {
             {
                 let lock = latest.lock().expect("Failed to lock");
                 switch_to_active(lock.active.as_ref(), &lock.hypr_data).warn("Failed to switch");
+                emit(Event::Closed {
+                    selected: lock.active.clone(),
+                });
                 drop(lock);
             }
 
-            clear_recent_clients();
             reload_desktop_maps();
         }
     ));
@@ -139,15 +145,24 @@ pub(crate) fn gui_exec(share: &Share, selected: usize) {
                 let mut lock = latest.lock().expect("Failed to lock");
                 lock.launcher_config.selected = Some(selected);
                 if let Some(exec) = lock.launcher_config.execs.get(selected) {
-                    run_program(&exec.exec, &exec.path, exec.terminal);
-                    cache_run(&exec.exec).warn("Failed to cache run");
+                    run_program(
+                        exec,
+                        lock.launcher_config.default_terminal.as_deref(),
+                        lock.init_config.launch_backend,
+                        selected_workspace(lock.active.as_ref(), &lock.hypr_data).as_deref(),
+                    );
+                    record_launch(&exec.id).warn("Failed to record launch");
+                    emit(Event::Launched {
+                        name: exec.name.to_string(),
+                        exec: exec.exec.to_string(),
+                    });
                 } else {
                     warn!("Selected program (nr. {}) not found, closing", selected);
                 }
+                emit(Event::Closed { selected: None });
                 drop(lock);
             }
 
-            clear_recent_clients();
             reload_desktop_maps();
         }
     ));
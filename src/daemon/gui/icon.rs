@@ -1,5 +1,5 @@
 use crate::daemon::gui::maps::{add_path_for_icon, get_icon_path_by_name, icon_has_name, Source};
-use gtk4::{Image};
+use gtk4::{prelude::*, Image};
 use std::fs;
 use std::path::Path;
 use tracing::{span, trace, warn, Level};
@@ -27,7 +27,8 @@ pub fn load_icon_from_cache(name: &str, pic: &Image) -> Option<Box<str>> {
     }
 }
 
-pub fn set_icon(class: &str, pid: i32, image: &Image) {
+/// sets the icon of the client, the image is hidden if no icon was found and `show_default_icon` is false
+pub fn set_icon(class: &str, pid: i32, image: &Image, show_default_icon: bool) {
     let class = class.to_string();
     let image = image.clone();
     // glib::spawn_future_local(async move {
@@ -45,7 +46,7 @@ pub fn set_icon(class: &str, pid: i32, image: &Image) {
             .next()
             .unwrap_or_default()
             .split('/')
-            .last()
+            .next_back()
             .unwrap_or_default();
         if cmd.is_empty() {
             warn!("Failed to read cmdline for PID {}", pid);
@@ -62,6 +63,11 @@ pub fn set_icon(class: &str, pid: i32, image: &Image) {
         warn!("Failed to read cmdline for PID {}", pid);
     };
 
-    image.set_icon_name(Some("application-x-executable"));
+    if show_default_icon {
+        image.set_icon_name(Some("application-x-executable"));
+    } else {
+        trace!("No Icon found for {class}, hiding icon");
+        image.set_visible(false);
+    }
     // });
 }
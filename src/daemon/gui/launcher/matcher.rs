@@ -0,0 +1,244 @@
+use crate::daemon::gui::maps::{DesktopAction, DesktopFile};
+use std::collections::HashMap;
+
+const MATCH: i32 = 10;
+// first char of a word, e.g. `c` of `Visual Studio Code` or `f` of `firefox`
+const WORD_START: i32 = 15;
+// char directly after the previous matched char
+const CONSECUTIVE: i32 = 10;
+const PREFIX: i32 = 30;
+const EXACT: i32 = 50;
+
+/// score of an application for the text in the launcher, None if no field matches
+///
+/// matches in the name are preferred over generic name, keywords and comment, frequently launched applications are boosted
+pub(super) fn score_entry(
+    text: &str,
+    entry: &DesktopFile,
+    frecencies: &HashMap<String, f64>,
+) -> Option<i32> {
+    let name = fuzzy_score(text, &entry.name);
+    let generic_name = entry
+        .generic_name
+        .as_deref()
+        .and_then(|g| fuzzy_score(text, g))
+        .map(|s| s * 3 / 4);
+    let keywords = entry
+        .keywords
+        .iter()
+        .filter_map(|k| fuzzy_score(text, k))
+        .max()
+        .map(|s| s * 2 / 3);
+    let comment = entry
+        .comment
+        .as_deref()
+        .and_then(|c| fuzzy_score(text, c))
+        .map(|s| s / 2);
+
+    let score = [name, generic_name, keywords, comment]
+        .into_iter()
+        .flatten()
+        .max()?;
+    let frecency = frecencies.get(&*entry.id).copied().unwrap_or(0.0);
+    Some(score + frecency_boost(frecency))
+}
+
+/// score of an action of the application `entry`, matches the action name alone or with the application name (`firefox priv`)
+///
+/// launches of the application boost its actions too
+pub(super) fn score_action(
+    text: &str,
+    entry: &DesktopFile,
+    action: &DesktopAction,
+    frecencies: &HashMap<String, f64>,
+) -> Option<i32> {
+    let score = fuzzy_score(text, &action.name).max(fuzzy_score(
+        text,
+        &format!("{} {}", entry.name, action.name),
+    ))?;
+    let frecency = frecencies.get(&*entry.id).copied().unwrap_or(0.0);
+    Some(score + frecency_boost(frecency))
+}
+
+/// score of `text` as subsequence of `target` (case-insensitive), None if not all chars of text are in target in order
+///
+/// matches at word starts and consecutive matches score higher, skipped chars lower the score
+pub(super) fn fuzzy_score(text: &str, target: &str) -> Option<i32> {
+    // only the first char of the lowercase mapping to keep the positions of the word starts
+    let lowercase = |s: &str| {
+        s.chars()
+            .map(|c| c.to_lowercase().next().unwrap_or(c))
+            .collect::<Vec<_>>()
+    };
+    let text = lowercase(text.trim());
+    if text.is_empty() {
+        return None;
+    }
+    let chars = target.chars().collect::<Vec<_>>();
+    let target = lowercase(target);
+
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for char in &text {
+        // continue the current match, else use the next word start or the next occurrence of the char
+        let next = previous
+            .map(|p| p + 1)
+            .filter(|&i| target.get(i) == Some(char))
+            .or_else(|| {
+                (position..target.len()).find(|&i| target[i] == *char && is_word_start(&chars, i))
+            })
+            .or_else(|| (position..target.len()).find(|&i| target[i] == *char))?;
+
+        score += MATCH;
+        if is_word_start(&chars, next) {
+            score += WORD_START;
+        }
+        match previous {
+            Some(p) if next == p + 1 => score += CONSECUTIVE,
+            // chars skipped between matches
+            Some(p) => score -= (next - p - 1).min(5) as i32,
+            // chars skipped before the first match
+            None => score -= next.min(5) as i32,
+        }
+        previous = Some(next);
+        position = next + 1;
+    }
+
+    if target.starts_with(&text) {
+        score += PREFIX;
+        if target.len() == text.len() {
+            score += EXACT;
+        }
+    }
+    Some(score)
+}
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    match index.checked_sub(1).map(|i| chars[i]) {
+        None => true,
+        Some(previous) => {
+            !previous.is_alphanumeric() || (previous.is_lowercase() && chars[index].is_uppercase())
+        }
+    }
+}
+
+/// recent launches (recorded in the history) boost the score, each doubling of the frecency adds less
+pub(super) fn frecency_boost(frecency: f64) -> i32 {
+    ((frecency.max(0.0) + 1.0).log2() * 8.0) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn entry(name: &str, generic_name: Option<&str>, keywords: &[&str]) -> DesktopFile {
+        DesktopFile {
+            id: format!("{}.desktop", name.to_lowercase()).into(),
+            name: name.into(),
+            generic_name: generic_name.map(Box::from),
+            comment: None,
+            icon: None,
+            keywords: keywords.iter().map(|k| Box::from(*k)).collect(),
+            exec: name.to_lowercase().into(),
+            command: None,
+            exec_path: None,
+            terminal: false,
+            file: Box::from(Path::new("/dev/null")),
+            actions: vec![],
+        }
+    }
+
+    /// names of the entries sorted by score like in the launcher
+    fn rank(text: &str, entries: &[DesktopFile], frecencies: &HashMap<String, f64>) -> Vec<String> {
+        let mut matches = entries
+            .iter()
+            .filter_map(|e| score_entry(text, e, frecencies).map(|s| (s, e.name.to_string())))
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, an), (b, bn)| b.cmp(a).then(an.cmp(bn)));
+        matches.into_iter().map(|(_, name)| name).collect()
+    }
+
+    #[test]
+    fn subsequence_matches() {
+        assert!(fuzzy_score("ff", "Firefox").is_some());
+        assert!(fuzzy_score("vsc", "Visual Studio Code").is_some());
+        assert!(fuzzy_score("FIRE", "firefox").is_some());
+        assert_eq!(fuzzy_score("xf", "Firefox"), None);
+        assert_eq!(fuzzy_score("", "Firefox"), None);
+    }
+
+    #[test]
+    fn word_starts_and_prefixes_score_higher() {
+        let vsc = fuzzy_score("vsc", "Visual Studio Code").unwrap();
+        let scattered = fuzzy_score("vsc", "Adventskalender Schema").unwrap();
+        assert!(vsc > scattered, "{vsc} <= {scattered}");
+
+        let prefix = fuzzy_score("term", "Terminal").unwrap();
+        let inner = fuzzy_score("term", "Xterminal").unwrap();
+        assert!(prefix > inner, "{prefix} <= {inner}");
+
+        assert!(fuzzy_score("code", "Code").unwrap() > fuzzy_score("code", "Codex").unwrap());
+        // camel case words
+        assert!(
+            fuzzy_score("lo", "LibreOffice").unwrap() > fuzzy_score("lo", "Libreoffice").unwrap()
+        );
+    }
+
+    #[test]
+    fn ranks_name_over_other_fields() {
+        let entries = [
+            entry("Firefox", Some("Web Browser"), &["Internet", "WWW"]),
+            entry("Files", Some("File Manager"), &["folder", "explorer"]),
+            entry("Chromium", Some("Web Browser"), &[]),
+            entry("Alacritty", Some("Terminal"), &["shell"]),
+        ];
+        let none = HashMap::new();
+        assert_eq!(rank("ff", &entries, &none), vec!["Firefox"]);
+        assert_eq!(rank("fi", &entries, &none), vec!["Files", "Firefox"]);
+        assert_eq!(rank("term", &entries, &none), vec!["Alacritty"]);
+        assert_eq!(
+            rank("browser", &entries, &none),
+            vec!["Chromium", "Firefox"]
+        );
+    }
+
+    #[test]
+    fn actions() {
+        let mut firefox = entry("Firefox", Some("Web Browser"), &[]);
+        firefox.actions = vec![DesktopAction {
+            id: "new-private-window".into(),
+            name: "New Private Window".into(),
+            icon: None,
+            exec: "firefox --private-window".into(),
+            command: None,
+        }];
+        let action = &firefox.actions[0];
+        let none = HashMap::new();
+        assert!(score_action("private", &firefox, action, &none).is_some());
+        assert!(score_action("firefox priv", &firefox, action, &none).is_some());
+        assert_eq!(score_action("chromium", &firefox, action, &none), None);
+        // the application itself ranks above its actions
+        assert!(
+            score_entry("firefox", &firefox, &none).unwrap()
+                > score_action("firefox", &firefox, action, &none).unwrap()
+        );
+    }
+
+    #[test]
+    fn frequently_launched_rank_higher() {
+        let entries = [
+            entry("Files", Some("File Manager"), &[]),
+            entry("Firefox", Some("Web Browser"), &[]),
+        ];
+        let frecencies = HashMap::from([("firefox.desktop".to_string(), 20.0)]);
+        assert_eq!(
+            rank("fi", &entries, &HashMap::new()),
+            vec!["Files", "Firefox"]
+        );
+        assert_eq!(rank("fi", &entries, &frecencies), vec!["Firefox", "Files"]);
+        assert_eq!(frecency_boost(0.0), 0);
+        assert!(frecency_boost(1.0) < frecency_boost(10.0));
+    }
+}
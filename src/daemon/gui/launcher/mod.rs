@@ -1,10 +1,11 @@
 use crate::daemon::gui::gui_handle::{
     gui_change_entry_input, gui_change_selected_program, gui_exec,
 };
+use crate::daemon::gui::launcher::matcher::{score_action, score_entry};
 use crate::daemon::gui::maps::get_all_desktop_files;
 use crate::daemon::gui::LauncherRefs;
-use crate::envs::{LAUNCHER_ANIMATE_LAUNCH_TIME, LAUNCHER_MAX_ITEMS, SHOW_LAUNCHER_EXECS};
-use crate::{Exec, GUISend, LaunchState, ReverseKey, Share, UpdateCause, Warn};
+use crate::daemon::history::get_frecencies;
+use crate::{Exec, GUISend, LaunchState, LauncherConfig, ReverseKey, Share, UpdateCause, Warn};
 use async_channel::Sender;
 use gtk4::gdk::{Key, Texture};
 use gtk4::glib::{clone, ControlFlow, Propagation};
@@ -22,6 +23,8 @@ use std::thread;
 use std::time::Duration;
 use tracing::{info, trace};
 
+mod matcher;
+
 pub(super) fn create_launcher(
     app: &Application,
     share: &Share,
@@ -104,10 +107,10 @@ pub(super) fn update_launcher(
     share: Share,
     text: &str,
     list: &ListBox,
-    selected: Option<usize>,
-    launch_state: LaunchState,
+    launcher_config: &LauncherConfig,
     reverse_key: &ReverseKey,
 ) -> Vec<Exec> {
+    let selected = launcher_config.selected;
     while let Some(child) = list.first_child() {
         list.remove(&child);
     }
@@ -119,34 +122,42 @@ pub(super) fn update_launcher(
     let mut execs = Vec::new();
 
     let entries = get_all_desktop_files();
-    let mut matches = Vec::new();
-    for (name, icon, _, exec, path, terminal, _) in entries.deref() {
-        if name
-            .to_ascii_lowercase()
-            .contains(&text.to_ascii_lowercase())
-        {
-            matches.push((name, icon, exec, path, terminal));
-        }
-    }
-    for (name, icon, keywords, exec, path, terminal, _) in entries.deref() {
-        if keywords
-            .iter()
-            .any(|k| k.to_ascii_lowercase().contains(&text.to_ascii_lowercase()))
-            && !matches.iter().any(|(n, _, _, _, _)| name.eq(n))
-        {
-            matches.push((name, icon, exec, path, terminal));
-        }
-    }
+    let frecencies = get_frecencies();
+    let frecencies = &frecencies;
+    // applications and their actions (as separate entries)
+    let mut matches = entries
+        .iter()
+        .flat_map(|entry| {
+            let app = score_entry(text, entry, frecencies).map(|score| (score, entry, None));
+            let actions = entry.actions.iter().filter_map(move |action| {
+                score_action(text, entry, action, frecencies)
+                    .map(|score| (score, entry, Some(action)))
+            });
+            app.into_iter().chain(actions)
+        })
+        .collect::<Vec<_>>();
+    // highest score first, same scores sorted by name (applications before their actions)
+    matches.sort_by(|(a, a_entry, a_action), (b, b_entry, b_action)| {
+        b.cmp(a)
+            .then(a_entry.name.cmp(&b_entry.name))
+            .then_with(|| a_action.map(|a| &a.name).cmp(&b_action.map(|b| &b.name)))
+    });
 
-    for (index, (name, icon, exec, path, terminal)) in
-        matches.into_iter().take(*LAUNCHER_MAX_ITEMS).enumerate()
+    for (index, (_, entry, action)) in matches
+        .into_iter()
+        .take(launcher_config.max_items)
+        .enumerate()
     {
+        let (name, icon, exec, command) = match action {
+            Some(action) => (&action.name, &action.icon, &action.exec, &action.command),
+            None => (&entry.name, &entry.icon, &entry.exec, &entry.command),
+        };
         let i = index as i32 - selected.unwrap_or(0) as i32;
         let widget = create_launch_widget(
             share.clone(),
             name,
             icon,
-            exec,
+            launcher_config.show_execs.then_some(&**exec),
             index,
             &match reverse_key {
                 ReverseKey::Mod(m) => match i {
@@ -168,16 +179,24 @@ pub(super) fn update_launcher(
                 }
             },
             if selected == Some(index) {
-                Some(launch_state)
+                Some(launcher_config.launch_state)
             } else {
                 None
             },
         );
+        if action.is_some() {
+            widget.add_css_class("launcher-item-action");
+            widget.set_tooltip_text(Some(&entry.name));
+        }
         list.append(&widget);
         execs.push(Exec {
+            // launches of actions count for the application
+            id: entry.id.clone(),
+            name: name.clone(),
             exec: exec.clone(),
-            path: path.clone(),
-            terminal: *terminal,
+            command: command.clone(),
+            path: entry.exec_path.clone(),
+            terminal: entry.terminal,
         });
     }
 
@@ -188,7 +207,7 @@ fn create_launch_widget(
     share: Share,
     name: &str,
     icon_path: &Option<Box<str>>,
-    exec: &str,
+    exec: Option<&str>,
     raw_index: usize,
     index: &str,
     selected: Option<LaunchState>,
@@ -229,7 +248,7 @@ fn create_launch_widget(
         .build();
     hbox.append(&title);
 
-    if *SHOW_LAUNCHER_EXECS {
+    if let Some(exec) = exec {
         let exec = Label::builder()
             .halign(Align::Start)
             .valign(Align::Center)
@@ -288,11 +307,11 @@ pub(crate) fn click_entry(share: &Share, selected: usize) -> GestureClick {
 pub fn show_launch_spawn(share: Share, cause: Option<u8>) {
     thread::spawn(move || {
         let (latest, send, receive) = share.deref();
-        {
+        let animate_launch_time = {
             let mut lat = latest.lock().expect("Failed to lock");
             lat.launcher_config.launch_state = LaunchState::Launching;
-            drop(lat);
-        }
+            lat.init_config.launcher_animate_launch_time
+        };
 
         trace!("Sending refresh to GUI");
         send.send_blocking((GUISend::Refresh, UpdateCause::BackgroundThread(cause)))
@@ -301,7 +320,7 @@ pub fn show_launch_spawn(share: Share, cause: Option<u8>) {
         trace!("Received refresh finish from GUI: {rec:?}");
 
         // wait for the GUI to update
-        thread::sleep(Duration::from_millis(*LAUNCHER_ANIMATE_LAUNCH_TIME));
+        thread::sleep(Duration::from_millis(animate_launch_time));
 
         {
             let mut lat = latest.lock().expect("Failed to lock");
@@ -1,10 +1,16 @@
+use crate::daemon::gui::desktop_entry::{
+    collect_desktop_files, get_current_desktops, get_locales, parse_exec, strip_field_codes,
+    DesktopEntry,
+};
+use crate::daemon::history::{migrate_run_cache, prune_history};
 use crate::Warn;
+use anyhow::Context;
 use gtk4::IconTheme;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 use std::sync::{Mutex, MutexGuard};
 use std::time::Instant;
-use std::{env, fs::DirEntry, path::PathBuf, sync::OnceLock};
+use std::{env, path::PathBuf, sync::OnceLock};
 use tracing::{debug, span, trace, warn, Level};
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -16,15 +22,37 @@ pub enum Source {
 }
 
 type IconPathMap = HashMap<(Box<str>, Source), (Box<str>, Box<Path>)>;
-type DesktopFileMap = Vec<(
-    Box<str>,
-    Option<Box<str>>,
-    Vec<Box<str>>,
-    Box<str>,
-    Option<Box<str>>,
-    bool,
-    Box<Path>,
-)>;
+type DesktopFileMap = Vec<DesktopFile>;
+
+/// application that can be started from the launcher
+#[derive(Debug, Clone)]
+pub struct DesktopFile {
+    /// desktop file ID, e.g. `firefox.desktop`
+    pub id: Box<str>,
+    pub name: Box<str>,
+    pub generic_name: Option<Box<str>>,
+    pub comment: Option<Box<str>>,
+    pub icon: Option<Box<str>>,
+    pub keywords: Vec<Box<str>>,
+    /// `Exec` without field codes
+    pub exec: Box<str>,
+    /// program and arguments of `Exec`, None if it has to be run with a shell
+    pub command: Option<Vec<Box<str>>>,
+    pub exec_path: Option<Box<str>>,
+    pub terminal: bool,
+    pub file: Box<Path>,
+    pub actions: Vec<DesktopAction>,
+}
+
+/// additional action of an application from `Actions=` like `New Private Window`
+#[derive(Debug, Clone)]
+pub struct DesktopAction {
+    pub id: Box<str>,
+    pub name: Box<str>,
+    pub icon: Option<Box<str>>,
+    pub exec: Box<str>,
+    pub command: Option<Vec<Box<str>>>,
+}
 
 fn get_icon_map() -> &'static Mutex<BTreeSet<Box<str>>> {
     static MAP_LOCK: OnceLock<Mutex<BTreeSet<Box<str>>>> = OnceLock::new();
@@ -82,18 +110,22 @@ pub fn reload_desktop_maps() {
         .expect("Failed to lock desktop file map");
     map2.clear();
     fill_desktop_file_map(&mut map, Some(&mut map2)).warn("Failed to fill desktop file map");
+    // don't remove the whole history if no desktop files could be read
+    if !map2.is_empty() {
+        let installed = map2.iter().map(|f| &*f.id).collect::<HashSet<_>>();
+        prune_history(&installed).warn("Failed to prune history");
+        let id_by_exec = map2
+            .iter()
+            .map(|f| (&*f.exec, &f.id))
+            .collect::<HashMap<_, _>>();
+        migrate_run_cache(|exec| id_by_exec.get(exec).map(|&id| id.clone()))
+            .warn("Failed to migrate the run cache");
+    }
 }
 
+/// `applications` dirs ordered by priority, `XDG_DATA_HOME` first so users can override system desktop files
 fn find_application_dirs() -> Vec<PathBuf> {
-    let mut dirs = env::var_os("XDG_DATA_DIRS")
-        .map(|val| env::split_paths(&val).map(PathBuf::from).collect())
-        .unwrap_or_else(|| {
-            vec![
-                PathBuf::from("/usr/local/share"),
-                PathBuf::from("/usr/share"),
-            ]
-        });
-
+    let mut dirs = Vec::new();
     if let Some(data_home) = env::var_os("XDG_DATA_HOME").map(PathBuf::from).map_or_else(
         || {
             env::var_os("HOME")
@@ -108,6 +140,17 @@ fn find_application_dirs() -> Vec<PathBuf> {
         dirs.push(data_home);
     }
 
+    dirs.extend(
+        env::var_os("XDG_DATA_DIRS")
+            .map(|val| env::split_paths(&val).collect())
+            .unwrap_or_else(|| {
+                vec![
+                    PathBuf::from("/usr/local/share"),
+                    PathBuf::from("/usr/share"),
+                ]
+            }),
+    );
+
     let mut res = Vec::new();
     for dir in dirs {
         res.push(dir.join("applications"));
@@ -116,31 +159,6 @@ fn find_application_dirs() -> Vec<PathBuf> {
     res
 }
 
-fn collect_desktop_files() -> Vec<DirEntry> {
-    let mut res = Vec::new();
-    for dir in find_application_dirs() {
-        if !dir.exists() {
-            continue;
-        }
-        match dir.read_dir() {
-            Ok(dir) => {
-                for entry in dir.flatten() {
-                    let path = entry.path();
-                    if path.is_file() && path.extension().is_some_and(|e| e == "desktop") {
-                        res.push(entry);
-                    }
-                }
-            }
-            Err(e) => {
-                warn!("Failed to read dir {dir:?}: {e}");
-                continue;
-            }
-        }
-    }
-    debug!("found {} desktop files", res.len());
-    res
-}
-
 fn fill_desktop_file_map(
     map: &mut IconPathMap,
     mut map2: Option<&mut DesktopFileMap>,
@@ -148,122 +166,123 @@ fn fill_desktop_file_map(
     let _span = span!(Level::TRACE, "fill_desktop_file_map").entered();
 
     let now = Instant::now();
-    for entry in collect_desktop_files() {
-        std::fs::read_to_string(entry.path())
-            .map(|content| {
-                let lines: Vec<&str> = content.lines().collect();
-                let icon = lines
-                    .iter()
-                    .find(|l| l.starts_with("Icon="))
-                    .map(|l| l.trim_start_matches("Icon="));
-
-                let name = lines
-                    .iter()
-                    .find(|l| l.starts_with("Name="))
-                    .map(|l| l.trim_start_matches("Name="));
-                let exec_name = lines
-                    .iter()
-                    .find(|l| l.starts_with("Exec="))
-                    .map(|l| l.trim_start_matches("Exec="))
-                    .map(|l| {
-                        // is a flatpak and isn't a PWA
-                        // (PWAs work out of the box by using the class = to the icon-name)
-                        // else chromium/chrome/etc would be detected as exec
-                        if l.contains("flatpak")
-                            && l.contains("--command")
-                            && !l.contains("--app-id")
-                        {
-                            // trim all text until --command
-                            l.split("--command=").last().unwrap_or(l)
-                        } else {
-                            l
-                        }
-                    })
-                    .and_then(|l| l.split(' ').next())
-                    .and_then(|l| l.split('/').last())
-                    .map(|n| n.replace('"', ""));
-                let startup_wm_class = lines
-                    .iter()
-                    .find(|l| l.starts_with("StartupWMClass="))
-                    .map(|l| l.trim_start_matches("StartupWMClass="));
+    let locales = get_locales();
+    let desktops = get_current_desktops();
+    let mut filled = HashSet::new();
+    for (id, path) in collect_desktop_files(&find_application_dirs()) {
+        let Some(entry) = std::fs::read_to_string(&path)
+            .context("Failed to read file")
+            .and_then(|content| DesktopEntry::parse(&content))
+            .warn(&format!("Failed to parse desktop file {path:?}"))
+        else {
+            continue;
+        };
+        let main = entry.main();
+        // hidden entries are treated as deleted (also hides desktop files with the same ID in other dirs)
+        if main.bool("Hidden") {
+            trace!("Skipping hidden desktop file {path:?}");
+            continue;
+        }
 
-                if let (Some(name), Some(icon)) = (name, icon) {
-                    map.insert(
-                        (Box::from(name.to_lowercase()), Source::DesktopFileName),
-                        (Box::from(icon), entry.path().into_boxed_path()),
-                    );
+        let icon = main.string("Icon");
+        let raw_exec = main.string("Exec");
+        let exec = raw_exec.as_deref().map(strip_field_codes);
+        let exec_name = exec
+            .as_deref()
+            .map(|l| {
+                // is a flatpak and isn't a PWA
+                // (PWAs work out of the box by using the class = to the icon-name)
+                // else chromium/chrome/etc would be detected as exec
+                if l.contains("flatpak") && l.contains("--command") && !l.contains("--app-id") {
+                    // trim all text until --command
+                    l.split("--command=").last().unwrap_or(l)
+                } else {
+                    l
                 }
-                if let (Some(startup_wm_class), Some(icon)) = (startup_wm_class, icon) {
-                    map.insert(
-                        (
-                            Box::from(startup_wm_class.to_lowercase()),
-                            Source::DesktopFileStartupWmClass,
-                        ),
-                        (Box::from(icon), entry.path().into_boxed_path()),
-                    );
-                }
-                if let (Some(exec_name), Some(icon)) = (exec_name, icon) {
+            })
+            .and_then(|l| l.split(' ').next())
+            .and_then(|l| l.split('/').next_back())
+            .map(|n| n.replace('"', ""));
+
+        if let Some(icon) = &icon {
+            // windows are matched by the untranslated name
+            let keys = [
+                (main.string("Name"), Source::DesktopFileName),
+                (
+                    main.string("StartupWMClass"),
+                    Source::DesktopFileStartupWmClass,
+                ),
+                (exec_name, Source::DesktopFileExecName),
+            ];
+            for (key, source) in keys {
+                let Some(key) = key else {
+                    continue;
+                };
+                let key = (Box::from(key.to_lowercase()), source);
+                // files are ordered by priority, keep the icon of the first file with this key
+                if filled.insert(key.clone()) {
                     map.insert(
-                        (
-                            Box::from(exec_name.to_lowercase()),
-                            Source::DesktopFileExecName,
-                        ),
-                        (Box::from(icon), entry.path().into_boxed_path()),
+                        key,
+                        (Box::from(icon.as_str()), path.clone().into_boxed_path()),
                     );
                 }
+            }
+        }
 
-                if let Some(ref mut map2) = map2 {
-                    let ttype = lines
-                        .iter()
-                        .find(|l| l.starts_with("Type="))
-                        .map(|l| l.trim_start_matches("Type="));
-                    let exec = lines
-                        .iter()
-                        .find(|l| l.starts_with("Exec="))
-                        .map(|l| l.trim_start_matches("Exec="));
-                    let keywords = lines
-                        .iter()
-                        .find(|l| l.starts_with("Keywords="))
-                        .map(|l| l.trim_start_matches("Keywords="));
-                    let no_display = lines
-                        .iter()
-                        .find(|l| l.starts_with("NoDisplay="))
-                        .map(|l| l.trim_start_matches("NoDisplay="))
-                        .map(|l| l == "true");
-                    let exec_path = lines
-                        .iter()
-                        .find(|l| l.starts_with("Path="))
-                        .and_then(|l| l.split('=').nth(1));
-                    let terminal = lines
-                        .iter()
-                        .find(|l| l.starts_with("Terminal="))
-                        .map(|l| l.trim_start_matches("Terminal="))
-                        .map(|l| l == "true")
-                        .unwrap_or(false);
-                    if ttype == Some("Application") && no_display.map_or(true, |n| !n) {
-                        if let (Some(name), Some(exec)) = (name, exec) {
-                            let mut exec = String::from(exec);
-                            for repl in &["%f", "%F", "%u", "%U"] {
-                                if exec.contains(repl) {
-                                    exec = exec.replace(repl, "");
-                                }
-                            }
-                            map2.push((
-                                name.trim().into(),
-                                icon.map(Box::from),
-                                keywords
-                                    .map(|k| k.split(';').map(|k| k.trim().into()).collect())
-                                    .unwrap_or_else(Vec::new),
-                                exec.trim().into(),
-                                exec_path.map(Box::from),
-                                terminal,
-                                entry.path().into_boxed_path(),
-                            ));
-                        }
-                    }
-                }
-            })
-            .warn(&format!("Failed to read file: {:?}", entry.path()));
+        if let Some(ref mut map2) = map2 {
+            if !entry.is_shown(&desktops) {
+                continue;
+            }
+            if let (Some(name), Some(exec), Some(raw_exec)) =
+                (main.locale_string("Name", &locales), exec, raw_exec)
+            {
+                let command = |exec: &str| {
+                    parse_exec(exec, &name, icon.as_deref(), &path)
+                        .map_err(|e| debug!("Exec {exec:?} of {path:?} is run with a shell: {e}"))
+                        .ok()
+                        .map(|args| args.into_iter().map(Box::from).collect())
+                };
+                map2.push(DesktopFile {
+                    id,
+                    name: name.trim().into(),
+                    generic_name: main
+                        .locale_string("GenericName", &locales)
+                        .map(|g| g.trim().into()),
+                    comment: main
+                        .locale_string("Comment", &locales)
+                        .map(|c| c.trim().into()),
+                    icon: icon.clone().map(Box::from),
+                    keywords: main
+                        .locale_strings("Keywords", &locales)
+                        .into_iter()
+                        .map(Box::from)
+                        .collect(),
+                    exec: exec.into(),
+                    command: command(&raw_exec),
+                    exec_path: main.string("Path").map(Box::from),
+                    terminal: main.bool("Terminal"),
+                    file: path.clone().into_boxed_path(),
+                    actions: entry
+                        .actions()
+                        .into_iter()
+                        .filter_map(|(id, action)| {
+                            let exec = action.string("Exec")?;
+                            Some(DesktopAction {
+                                id: id.into(),
+                                name: action.locale_string("Name", &locales)?.trim().into(),
+                                // actions without an icon use the icon of the application
+                                icon: action
+                                    .string("Icon")
+                                    .or_else(|| icon.clone())
+                                    .map(Box::from),
+                                exec: strip_field_codes(&exec).into(),
+                                command: command(&exec),
+                            })
+                        })
+                        .collect(),
+                });
+            }
+        }
     }
     debug!("filled icon map in {}ms", now.elapsed().as_millis());
     Ok(())
@@ -277,7 +296,6 @@ pub(in crate::daemon::gui) fn get_icon_name_debug(
     find_icon_path_by_name(map, icon)
 }
 
-#[allow(clippy::type_complexity)]
 pub(in crate::daemon::gui) fn get_desktop_files_debug() -> anyhow::Result<DesktopFileMap> {
     let mut map = HashMap::new();
     let mut map2 = Vec::new();
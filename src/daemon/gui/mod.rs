@@ -1,4 +1,3 @@
-use crate::envs::SHOW_LAUNCHER;
 use crate::{GUISend, InitConfig, Payload, Share, SubmapConfig, UpdateCause, Warn};
 use anyhow::Context;
 use async_channel::{Receiver, RecvError, Sender};
@@ -22,10 +21,11 @@ use std::rc::Rc;
 use std::sync::Mutex;
 use tracing::{debug, error, info, span, trace, warn, Level};
 
-pub use debug::{debug_desktop_files, debug_list, debug_search_class};
+pub use debug::{debug_desktop_files, debug_history, debug_list, debug_search_class};
 pub use maps::reload_desktop_maps;
 
 mod debug;
+mod desktop_entry;
 mod gui_handle;
 mod icon;
 mod launcher;
@@ -77,13 +77,12 @@ pub(super) fn start_gui_blocking(
             drop(monitor_data_list);
         }
 
+        // created when the first GUI with launcher is opened
         let launcher_refs: LauncherRefs = Rc::new(Mutex::new(None));
-        if *SHOW_LAUNCHER {
-            launcher::create_launcher(app, &share, launcher_refs.clone(), visibility_sender)
-                .warn("Failed to create launcher");
-        }
 
         glib::spawn_future_local(clone!(
+            #[strong]
+            app,
             #[strong]
             share,
             #[strong]
@@ -96,10 +95,15 @@ pub(super) fn start_gui_blocking(
             return_sender,
             #[strong]
             launcher_refs,
+            #[strong]
+            visibility_sender,
             async move {
                 loop {
                     trace!("Waiting for GUI update");
                     let mess = receiver.recv().await;
+                    if let Ok((GUISend::New, _)) = mess {
+                        create_launcher_if_shown(&app, &share, &launcher_refs, &visibility_sender);
+                    }
                     handle_update(
                         &share,
                         &init_config,
@@ -124,6 +128,25 @@ pub(super) fn start_gui_blocking(
     error!("Application exited");
 }
 
+fn create_launcher_if_shown(
+    app: &Application,
+    share: &Share,
+    launcher: &LauncherRefs,
+    visibility_sender: &Sender<bool>,
+) {
+    let (shared_data, _, _) = share.deref();
+    let show = shared_data
+        .lock()
+        .expect("Failed to lock, shared_data")
+        .launcher_config
+        .show;
+    if show && launcher.lock().expect("Failed to lock, launcher").is_none() {
+        debug!("Creating launcher");
+        launcher::create_launcher(app, share, launcher.clone(), visibility_sender.clone())
+            .warn("Failed to create launcher");
+    }
+}
+
 async fn handle_update(
     share: &Share,
     init_config: &InitConfig,
@@ -152,7 +175,7 @@ async fn handle_update(
                     }
 
                     // TODO only open when using --close = default
-                    if data.gui_config.show_launcher {
+                    if data.launcher_config.show {
                         let workspaces = data
                             .hypr_data
                             .workspaces
@@ -184,16 +207,15 @@ async fn handle_update(
                         &data.hypr_data.workspaces,
                         &data.hypr_data.clients,
                         monitor_data,
-                        init_config.show_title,
                         data.gui_config.show_workspaces_on_all_monitors,
-                        init_config.size_factor,
+                        init_config,
                     );
 
                     trace!("Refresh window {:?}", window);
                     windows::update_windows(monitor_data, &data).warn("Failed to update windows");
                 }
                 // only open launcher when opening with default close mode
-                if data.gui_config.show_launcher {
+                if data.launcher_config.show {
                     launcher.as_ref().inspect(|(window, entry, _)| {
                         trace!("Showing launcher {:?}", window);
                         windows += 1;
@@ -223,7 +245,7 @@ async fn handle_update(
             let launcher = launcher.lock().expect("Failed to lock, launcher");
 
             // only update launcher wen using default close mode
-            if data.gui_config.show_launcher {
+            if data.launcher_config.show {
                 launcher.as_ref().inspect(|(_, e, l)| {
                     if data.launcher_config.selected.is_none() && !e.text().is_empty() {
                         data.launcher_config.selected = Some(0);
@@ -239,8 +261,7 @@ async fn handle_update(
                         share.clone(),
                         &e.text(),
                         l,
-                        data.launcher_config.selected,
-                        data.launcher_config.launch_state,
+                        &data.launcher_config,
                         reverse_key,
                     );
                     data.launcher_config.execs = execs;
@@ -264,7 +285,7 @@ async fn handle_update(
                 let launcher = launcher.lock().expect("Failed to lock, launcher");
 
                 let mut windows = 0;
-                if data.gui_config.show_launcher {
+                if data.launcher_config.show {
                     launcher.as_ref().inspect(|(window, _, _)| {
                         trace!("Hiding launcher {:?}", window);
                         windows += 1;
@@ -1,27 +1,37 @@
 use crate::daemon::gui::icon::set_icon;
 use crate::daemon::gui::windows::click::{click_client, click_workspace};
 use crate::daemon::gui::MonitorData;
-use crate::envs::REMOVE_HTML_FROM_WORKSPACE_NAME;
-use crate::{ClientData, Share, WorkspaceData};
+use crate::{ClientData, InitConfig, Share, WorkspaceData};
 use gtk4::{pango, prelude::*, Fixed, Frame, Image, Label, Overflow, Overlay};
 use hyprland::shared::{Address, WorkspaceId};
 use regex::Regex;
 use std::borrow::Cow;
-use std::cmp::min;
 
-fn scale(value: i16, size_factor: f64) -> i32 {
+// smaller icons are not recognizable
+const MIN_ICON_SIZE: i32 = 16;
+
+fn scale(value: i32, size_factor: f64) -> i32 {
     (value as f64 / 30.0 * size_factor) as i32
 }
 
+/// pixel size of the icon of a client (limited by `max_size`), None if the client is too small to show an icon
+fn icon_size(width: i32, height: i32, size_factor: f64, max_size: u16) -> Option<i32> {
+    // leave space for the title and the border of the frame
+    let space = scale(width, size_factor).min(scale(height, size_factor));
+    let size = ((space as f64 / 1.5) as i32 - 20).min(max_size as i32);
+    (size >= MIN_ICON_SIZE).then_some(size)
+}
+
 pub fn init_windows(
     share: Share,
     workspaces_p: &[(WorkspaceId, WorkspaceData)],
     clients_p: &[(Address, ClientData)],
     monitor_data: &mut MonitorData,
-    show_title: bool,
     show_workspaces_on_all_monitors: bool,
-    size_factor: f64,
+    init_config: &InitConfig,
 ) {
+    let show_title = init_config.show_title;
+    let size_factor = init_config.size_factor;
     clear_monitor(monitor_data);
 
     let workspaces = {
@@ -29,20 +39,20 @@ pub fn init_windows(
             .iter()
             .filter(|(_, v)| show_workspaces_on_all_monitors || v.monitor == monitor_data.id)
             .collect::<Vec<_>>();
-        workspaces.sort_by(|(a, _), (b, _)| a.cmp(b));
+        workspaces.sort_by_key(|(a, _)| *a);
         workspaces
     };
 
     let regex = Regex::new(r"<span[^>]*>(.*?)</span>").expect("Failed to create regex");
     for (wid, workspace) in workspaces {
         let workspace_fixed = Fixed::builder()
-            .width_request(scale(workspace.width as i16, size_factor))
-            .height_request(scale(workspace.height as i16, size_factor))
+            .width_request(scale(workspace.width as i32, size_factor))
+            .height_request(scale(workspace.height as i32, size_factor))
             .build();
 
         let id_string = wid.to_string();
         let title = if show_title && !workspace.name.trim().is_empty() {
-            if *REMOVE_HTML_FROM_WORKSPACE_NAME {
+            if init_config.remove_html_from_workspace_name {
                 regex.replace_all(&workspace.name, "$1")
             } else {
                 Cow::from(&workspace.name)
@@ -108,31 +118,25 @@ pub fn init_windows(
                     .build();
 
                 // hide picture if client so small
-                // 2 => > infinity
-                // 2.1  > 800
-                // 3 => > 800
-                // 3.9  > 800
-                // 4 => > 538
-                // 5 => > 473
-                // 6 => > 408
-                // 7 => > 343
-                // 8 => > 278
-                // 9 => > 250
-                if match size_factor {
-                    ..2.5 => false,
-                    2.5..3.9 => client.height > 800,
-                    _ => client.height > 700 - min(((size_factor - 1.5) * 65.0) as i16, 450),
-                } {
+                if let Some(pixel_size) = icon_size(
+                    client.width,
+                    client.height,
+                    size_factor,
+                    init_config.icon_size,
+                ) {
                     let image = Image::builder()
                         .css_classes(vec!["client-image"])
-                        .pixel_size(
-                            (scale(client.height, size_factor).clamp(50, 200) as f64 / 1.5) as i32 - 20,
-                        )
+                        .pixel_size(pixel_size)
                         .build();
                     if !client.enabled {
                         image.add_css_class("monochrome");
                     }
-                    set_icon(&client.class, client.pid, &image);
+                    set_icon(
+                        &client.class,
+                        client.pid,
+                        &image,
+                        init_config.show_default_icon,
+                    );
                     client_frame.set_child(Some(&image));
                 }
 
@@ -148,8 +152,8 @@ pub fn init_windows(
             };
             workspace_fixed.put(
                 &client_overlay,
-                scale(client.x - workspace.x as i16, size_factor) as f64,
-                scale(client.y - workspace.y as i16, size_factor) as f64,
+                scale(client.x - workspace.x, size_factor) as f64,
+                scale(client.y - workspace.y, size_factor) as f64,
             );
             monitor_data
                 .client_refs
@@ -177,3 +181,30 @@ fn clear_monitor(monitor_data: &mut MonitorData) {
         .0
         .remove_css_class("monitor_active");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::icon_size;
+
+    #[test]
+    fn icon_size_scales_with_client() {
+        // 1920x1080 client with size_factor 6 => 216px high
+        assert_eq!(icon_size(1920, 1080, 6.0, 512), Some(124));
+        assert_eq!(icon_size(1920, 1080, 12.0, 512), Some(268));
+        // the smaller side of the client limits the icon
+        assert_eq!(icon_size(500, 1080, 6.0, 512), Some(46));
+    }
+
+    #[test]
+    fn icon_size_limited_by_max_size() {
+        assert_eq!(icon_size(1920, 1080, 12.0, 128), Some(128));
+        assert_eq!(icon_size(1920, 1080, 6.0, 64), Some(64));
+    }
+
+    #[test]
+    fn icon_size_hidden_for_small_clients() {
+        assert_eq!(icon_size(1920, 1080, 1.0, 512), None);
+        assert_eq!(icon_size(300, 200, 6.0, 512), None);
+        assert_eq!(icon_size(1920, 1080, 6.0, 8), None);
+    }
+}
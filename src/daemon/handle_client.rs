@@ -1,18 +1,72 @@
 use crate::client::daemon_running;
-use crate::daemon::handle_fns::{close, init, switch};
-use crate::envs::SYSTEMD_SERVICE;
-use crate::{get_socket_path_buff, global, toast, Share, Transfer, TransferType};
+use crate::daemon::binds::{register_binds, unregister_binds};
+use crate::daemon::handle_fns::{close, init, query, reload, switch};
+use crate::daemon::ordered::Ticket;
+use crate::daemon::subscribe::add_subscriber;
+use crate::envs::get_envs;
+use crate::{
+    get_socket_path_buff, global, toast, Response, ResponseError, ResponseType, Share, Transfer,
+    TransferType,
+};
 use anyhow::Context;
+use hyprland::shared::HyprError;
 use rand::Rng;
 use std::env;
 use std::fs::remove_file;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::process::exit;
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, trace, warn};
 use tracing::{span, Level};
 
+// time a client has to send its whole first message
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+// bincode messages are much smaller, keeps the first byte of the length prefix 0 (never `{`)
+const MAX_MESSAGE_LEN: usize = 1024 * 1024;
+
+/// reads from the stream until the deadline, a read timeout alone only limits every single read
+/// (a client sending one byte at a time would hold its ticket forever)
+struct DeadlineReader {
+    stream: UnixStream,
+    deadline: Option<Instant>,
+}
+
+impl DeadlineReader {
+    fn new(stream: UnixStream, timeout: Duration) -> Self {
+        Self {
+            stream,
+            deadline: Some(Instant::now() + timeout),
+        }
+    }
+}
+
+impl Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if let Some(deadline) = self.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(io::Error::new(
+                    ErrorKind::TimedOut,
+                    "Read deadline exceeded",
+                ));
+            }
+            self.stream.set_read_timeout(Some(remaining))?;
+        }
+        self.stream.read(buf)
+    }
+}
+
+/// first message of a client
+#[derive(Debug, PartialEq)]
+enum Message {
+    // newline-delimited json, more messages may follow
+    Json(Vec<u8>),
+    // bincode prefixed with its length (big-endian u32)
+    Bincode(Vec<u8>),
+}
+
 pub(super) fn start_handler_blocking(share: &Share) {
     if daemon_running() {
         warn!("Daemon already running");
@@ -30,15 +84,36 @@ pub(super) fn start_handler_blocking(share: &Share) {
     .expect("Unable to start Listener");
     info!("Starting listener on {path:?}");
 
+    // only registered after the socket is bound, as a second daemon exits above
+    let binds = share
+        .0
+        .lock()
+        .expect("Failed to lock")
+        .init_config
+        .binds
+        .clone();
+    if !binds.is_empty() {
+        register_binds(&binds)
+            .context("Failed to register binds")
+            .unwrap_or_else(|e| {
+                toast(&format!("Failed to register binds: {e:?}"));
+                warn!("{:?}", e)
+            });
+    }
+
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
+                // take the ticket when accepting so transitions are applied in the order clients connected
+                let ticket = Ticket::new();
                 let arc_share = share.clone();
-                handle_client(stream, arc_share).context("Failed to handle client")
+                thread::spawn(move || {
+                    handle_client(stream, arc_share, ticket).context("Failed to handle client")
                         .unwrap_or_else(|e| {
                             toast(&format!("Failed to handle client (restarting the hyprswitch daemon will most likely fix the issue) {:?}", e));
                             warn!("{:?}", e)
                         });
+                });
             }
             Err(e) => {
                 error!("Failed to accept client: {}", e);
@@ -47,36 +122,156 @@ pub(super) fn start_handler_blocking(share: &Share) {
     }
 }
 
-pub(super) fn handle_client(stream: UnixStream, share: Share) -> anyhow::Result<()> {
+pub(super) fn handle_client(
+    stream: UnixStream,
+    share: Share,
+    ticket: Ticket,
+) -> anyhow::Result<()> {
     let now = Instant::now();
     let rand_id = rand::rng().random_range(100..=255);
     let _span = span!(Level::TRACE, "handle_client", id = rand_id).entered();
 
+    // a hung client only blocks later transitions until the timeout
     let reader_stream = stream.try_clone().context("Failed to clone stream")?;
-    let mut reader = BufReader::new(reader_stream);
-    let mut buffer = Vec::new();
-    reader
-        .read_until(b'\n', &mut buffer)
-        .context("Failed to read data from buffer")?;
-
-    // client checked if socket is OK
-    if buffer.is_empty() {
-        // debug!("Received empty buffer");
-        return Ok(());
-    }
-    handle_client_transfer(stream, buffer, share, rand_id)?;
+    let mut reader = BufReader::new(DeadlineReader::new(reader_stream, READ_TIMEOUT));
+    match read_message(&mut reader) {
+        // client checked if socket is OK
+        Ok(None) => {}
+        Ok(Some(Message::Json(buffer))) => {
+            handle_client_json(stream, reader, buffer, share, rand_id, ticket)?
+        }
+        Ok(Some(Message::Bincode(buffer))) => {
+            handle_client_bincode(stream, buffer, share, rand_id, ticket)?
+        }
+        Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+            warn!("Client didn't send a message within {READ_TIMEOUT:?}");
+        }
+        Err(e) => return Err(e).context("Failed to read data from buffer"),
+    }
     trace!("Handled client in {:?}", now.elapsed());
     Ok(())
 }
 
-pub(super) fn handle_client_transfer(
+/// reads the first message, None if the client closed the stream without sending anything
+///
+/// json messages always start with `{` and end at the newline,
+/// bincode messages can contain any byte and are prefixed with their length instead
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Message>> {
+    let Some(&first) = reader.fill_buf()?.first() else {
+        return Ok(None);
+    };
+    if first == b'{' {
+        let mut buffer = Vec::new();
+        reader.read_until(b'\n', &mut buffer)?;
+        return Ok(Some(Message::Json(buffer)));
+    }
+
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_MESSAGE_LEN {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("Message of {len} bytes is longer than {MAX_MESSAGE_LEN} bytes"),
+        ));
+    }
+    let mut buffer = vec![0; len];
+    reader.read_exact(&mut buffer)?;
+    Ok(Some(Message::Bincode(buffer)))
+}
+
+fn handle_client_bincode(
     mut stream: UnixStream,
     buffer: Vec<u8>,
     share: Share,
     client_id: u8,
+    ticket: Ticket,
 ) -> anyhow::Result<()> {
     let transfer: Transfer = bincode::deserialize(&buffer)
         .with_context(|| format!("Failed to deserialize buffer {buffer:?}"))?;
+    let response = if matches!(transfer.transfer, TransferType::Subscribe) {
+        // events are json lines, which can't be mixed with bincode responses
+        Err(ResponseError::Failed(
+            "Subscribe is only supported with json messages".to_string(),
+        ))
+    } else {
+        handle_client_transfer_ordered(transfer, share, client_id, ticket)
+    };
+    return_response(response, &mut stream)?;
+    Ok(())
+}
+
+/// handles newline-delimited json messages until the client closes the stream (or subscribes to events)
+fn handle_client_json(
+    mut stream: UnixStream,
+    mut reader: BufReader<DeadlineReader>,
+    mut buffer: Vec<u8>,
+    share: Share,
+    client_id: u8,
+    mut ticket: Ticket,
+) -> anyhow::Result<()> {
+    // clients may keep the stream open between messages
+    reader.get_mut().deadline = None;
+    stream
+        .set_read_timeout(None)
+        .context("Failed to remove read timeout")?;
+    while !buffer.is_empty() {
+        let response = match serde_json::from_slice::<Transfer>(&buffer) {
+            Ok(transfer) => {
+                handle_client_transfer_ordered(transfer, share.clone(), client_id, ticket)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to deserialize json {:?}: {e}",
+                    String::from_utf8_lossy(&buffer)
+                );
+                Err(ResponseError::Failed(format!("Invalid json message: {e}")))
+            }
+        };
+        let subscribe = matches!(response, Ok(ResponseType::Subscribe));
+        return_response_json(response, &mut stream)?;
+        if subscribe {
+            // stream is kept open for events, don't read any more messages
+            add_subscriber(stream).context("Failed to add subscriber")?;
+            return Ok(());
+        }
+
+        buffer.clear();
+        reader
+            .read_until(b'\n', &mut buffer)
+            .context("Failed to read data from buffer")?;
+        ticket = Ticket::new();
+    }
+    Ok(())
+}
+
+/// applies transfers that change the state in the order of their tickets, read-only transfers are handled immediately
+fn handle_client_transfer_ordered(
+    transfer: Transfer,
+    share: Share,
+    client_id: u8,
+    ticket: Ticket,
+) -> Result<ResponseType, ResponseError> {
+    match transfer.transfer {
+        TransferType::VersionCheck
+        | TransferType::Open
+        | TransferType::Query
+        | TransferType::Subscribe => {
+            drop(ticket);
+            handle_client_transfer(transfer, share, client_id)
+        }
+        TransferType::Dispatch(_)
+        | TransferType::Init(..)
+        | TransferType::Close(_)
+        | TransferType::Reload => ticket.run(|| handle_client_transfer(transfer, share, client_id)),
+    }
+}
+
+pub(super) fn handle_client_transfer(
+    transfer: Transfer,
+    share: Share,
+    client_id: u8,
+) -> Result<ResponseType, ResponseError> {
     trace!("Received command: {transfer:?}");
 
     // check the major and minor number, exclude patch number
@@ -91,18 +286,18 @@ pub(super) fn handle_client_transfer(
             transfer.version,
             env!("CARGO_PKG_VERSION")
         );
-        if *SYSTEMD_SERVICE {
+        if get_envs().systemd_service {
             // automatically restart if in systemd mode
             debug!("Restarting daemon");
+            unregister_binds();
             exit(1);
         } else {
             toast(VERSION_OUT_OF_SYNC);
-            return_success(false, &mut stream)?;
-
-            // automatically restart if in systemd mode
-
             // don't return Error (would trigger new toast)
-            return Ok(());
+            return Err(ResponseError::VersionMismatch {
+                daemon: env!("CARGO_PKG_VERSION").to_string(),
+                client: transfer.version,
+            });
         }
     }
 
@@ -115,17 +310,27 @@ pub(super) fn handle_client_transfer(
     match transfer.transfer {
         TransferType::VersionCheck => {
             debug!("Received version check command"); // use debug here to not spam the logs
-            return_success(true, &mut stream)?;
+            Ok(ResponseType::VersionCheck(
+                env!("CARGO_PKG_VERSION").to_string(),
+            ))
         }
         TransferType::Open => {
             info!("Received open command");
-            return_success(open, &mut stream)?;
+            Ok(ResponseType::Open(open))
+        }
+        TransferType::Subscribe => {
+            info!("Received subscribe command");
+            Ok(ResponseType::Subscribe)
+        }
+        TransferType::Query => {
+            debug!("Received query command");
+            Ok(ResponseType::Query(Box::new(query(&share, open))))
         }
         TransferType::Init(config, gui_config, submap_config) => {
             if !open {
                 let _span = span!(Level::TRACE, "init").entered();
                 info!("Received init command {config:?} and {gui_config:?} and {submap_config:?}");
-                match init(
+                init(
                     &share,
                     config.clone(),
                     gui_config.clone(),
@@ -137,72 +342,97 @@ pub(super) fn handle_client_transfer(
                         "Failed to init with config {:?} and gui_config {:?}",
                         config, gui_config
                     )
-                }) {
-                    Ok(_) => {
-                        return_success(true, &mut stream)?;
-                    }
-                    Err(e) => {
-                        error!("{:?}", e);
-                        return_success(false, &mut stream)?;
-                    }
-                };
+                })
+                .map(|_| ResponseType::Init)
+                .map_err(to_response_error)
             } else {
-                return_success(false, &mut stream)?;
+                Err(ResponseError::AlreadyOpen)
             }
         }
         TransferType::Close(kill) => {
             if open {
                 let _span = span!(Level::TRACE, "close").entered();
                 info!("Received close command with kill: {kill}");
-                match close(&share, kill, client_id)
+                close(&share, kill, client_id)
                     .with_context(|| format!("Failed to close gui  kill: {kill}"))
-                {
-                    Ok(_) => {
-                        return_success(true, &mut stream)?;
-                    }
-                    Err(e) => {
-                        error!("{:?}", e);
-                        return_success(false, &mut stream)?;
-                    }
-                };
+                    .map(|_| ResponseType::Close)
+                    .map_err(to_response_error)
+            } else {
+                Err(ResponseError::NotOpen)
+            }
+        }
+        TransferType::Reload => {
+            if !open {
+                let _span = span!(Level::TRACE, "reload").entered();
+                info!("Received reload command");
+                reload(&share)
+                    .context("Failed to reload config")
+                    .map(|_| ResponseType::Reload)
+                    .map_err(to_response_error)
             } else {
-                return_success(false, &mut stream)?;
+                // windows can't be recreated while they are shown
+                Err(ResponseError::AlreadyOpen)
             }
         }
         TransferType::Dispatch(dispatch_config) => {
             if open {
                 let _span = span!(Level::TRACE, "switch").entered();
                 info!("Received switch command {dispatch_config:?}");
-                match switch(&share, &dispatch_config, client_id)
+                switch(&share, &dispatch_config, client_id)
                     .with_context(|| format!("Failed to execute with command {dispatch_config:?}"))
-                {
-                    Ok(_) => {
-                        return_success(true, &mut stream)?;
-                    }
-                    Err(e) => {
-                        error!("{:?}", e);
-                        return_success(false, &mut stream)?;
-                    }
-                };
+                    .map(|_| ResponseType::Dispatch)
+                    .map_err(to_response_error)
             } else {
-                return_success(false, &mut stream)?;
+                Err(ResponseError::NotOpen)
             }
         }
-    };
-
-    Ok(())
+    }
 }
 
-fn return_success(success: bool, stream: &mut UnixStream) -> anyhow::Result<()> {
-    if success {
-        stream
-            .write_all(b"1")
-            .with_context(|| "Failed to write data to socket".to_string())?;
+/// converts the error of a handle_fn into a [`ResponseError`] (keeps typed errors and marks errors from hyprland)
+fn to_response_error(err: anyhow::Error) -> ResponseError {
+    error!("{:?}", err);
+    if let Some(response_error) = err.downcast_ref::<ResponseError>() {
+        response_error.clone()
+    } else if err.chain().any(|e| e.is::<HyprError>()) {
+        ResponseError::HyprlandDispatch(format!("{err:#}"))
     } else {
-        stream
-            .write_all(b"0")
-            .with_context(|| "Failed to write data to socket".to_string())?;
+        ResponseError::Failed(format!("{err:#}"))
     }
+}
+
+fn return_response(
+    response: Result<ResponseType, ResponseError>,
+    stream: &mut UnixStream,
+) -> anyhow::Result<()> {
+    trace!("Returning response: {response:?}");
+    let response = Response {
+        response,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let serialized = bincode::serialize(&response)
+        .with_context(|| format!("Failed to serialize response {response:?}"))?;
+    stream
+        .write_all(&serialized)
+        .with_context(|| "Failed to write data to socket".to_string())?;
+    Ok(())
+}
+
+fn return_response_json(
+    response: Result<ResponseType, ResponseError>,
+    stream: &mut UnixStream,
+) -> anyhow::Result<()> {
+    trace!("Returning json response: {response:?}");
+    let response = Response {
+        response,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    let mut serialized = serde_json::to_vec(&response)
+        .with_context(|| format!("Failed to serialize response {response:?}"))?;
+    serialized.push(b'\n');
+    stream
+        .write_all(&serialized)
+        .with_context(|| "Failed to write data to socket".to_string())?;
     Ok(())
 }
 
@@ -212,3 +442,90 @@ You must manually start the new version (run `pkill hyprswitch && hyprswitch ini
 
 (visit https://github.com/H3rmt/hyprswitch/releases to see latest release and new features)
 ";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut buffer = (payload.len() as u32).to_be_bytes().to_vec();
+        buffer.extend_from_slice(payload);
+        buffer
+    }
+
+    #[test]
+    fn bincode_messages_with_newlines() {
+        let transfer = Transfer {
+            transfer: TransferType::Close(true),
+            version: "3.3\n.2".to_string(),
+        };
+        let payload = bincode::serialize(&transfer).expect("Failed to serialize");
+        assert!(payload.contains(&b'\n'));
+
+        let mut reader = Cursor::new(frame(&payload));
+        let Some(Message::Bincode(buffer)) = read_message(&mut reader).unwrap() else {
+            panic!("Expected a bincode message");
+        };
+        let read: Transfer = bincode::deserialize(&buffer).expect("Failed to deserialize");
+        assert!(matches!(read.transfer, TransferType::Close(true)));
+        assert_eq!(read.version, transfer.version);
+    }
+
+    #[test]
+    fn json_messages() {
+        let mut reader = Cursor::new(b"{\"transfer\": \"Open\"}\n{\"transfer\": \"Query\"}\n");
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Some(Message::Json(b"{\"transfer\": \"Open\"}\n".to_vec()))
+        );
+        assert_eq!(read_message(&mut Cursor::new(b"")).unwrap(), None);
+    }
+
+    #[test]
+    fn slow_clients_dont_block_later_transitions() {
+        let (mut client, server) = UnixStream::pair().expect("Failed to create stream");
+        let first = Ticket::new();
+        let second = Ticket::new();
+        // every byte arrives within the timeout of a single read
+        let writer = thread::spawn(move || {
+            for byte in b"{\"transfer\": \"Reload\"}\n" {
+                if client.write_all(&[*byte]).is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+        });
+
+        let start = Instant::now();
+        let reader = thread::spawn(move || {
+            let mut reader =
+                BufReader::new(DeadlineReader::new(server, Duration::from_millis(300)));
+            let message = read_message(&mut reader);
+            drop(first);
+            message
+        });
+        // runs once the first client timed out
+        let elapsed = second.run(|| start.elapsed());
+        assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+
+        let error = reader.join().unwrap().unwrap_err();
+        assert!(
+            matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut),
+            "{error:?}"
+        );
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn invalid_bincode_messages() {
+        // stream closed before the whole message was sent
+        let mut truncated = frame(b"message");
+        truncated.pop();
+        let error = read_message(&mut Cursor::new(truncated)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+        let error = read_message(&mut Cursor::new(u32::MAX.to_be_bytes())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
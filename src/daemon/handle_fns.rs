@@ -1,11 +1,21 @@
 use crate::configs::DispatchConfig;
-use crate::daemon::cache::cache_run;
+use crate::daemon::binds::register_binds;
 use crate::daemon::gui::{reload_desktop_maps, show_launch_spawn};
+use crate::daemon::history::record_launch;
+use crate::daemon::hypr_state::get_snapshot;
 use crate::daemon::submap::{activate_submap, deactivate_submap, generate_submap};
-use crate::handle::{clear_recent_clients, collect_data, find_next, run_program, switch_to_active};
-use crate::{global, GUISend, GuiConfig, Share, SimpleConfig, SubmapConfig, UpdateCause, Warn};
+use crate::daemon::subscribe::emit;
+use crate::handle::{
+    collect_data_from, find_next, run_program, selected_workspace, switch_to_active,
+};
+use crate::{
+    global, Active, Event, GUISend, GuiConfig, InitConfig, LauncherConfig, QueryClient, QueryData,
+    QueryLauncher, QueryMonitor, QueryWorkspace, Share, SimpleConfig, SubmapConfig, SwitchType,
+    UpdateCause, Warn,
+};
 use anyhow::Context;
 use std::ops::Deref;
+use std::path::Path;
 use tracing::{info, trace, warn};
 
 pub(crate) fn switch(
@@ -35,6 +45,10 @@ pub(crate) fn switch(
             )?;
             lock.active = Some(active);
         }
+        emit(Event::SelectionChanged {
+            selected: lock.active.clone(),
+            launcher_selected: lock.launcher_config.selected,
+        });
         drop(lock);
     }
 
@@ -56,12 +70,15 @@ pub(crate) fn init(
     submap_config: SubmapConfig,
     client_id: u8,
 ) -> anyhow::Result<()> {
-    let (clients_data, active) = collect_data(simple_config.clone()).with_context(|| {
-        format!(
-            "Failed to collect data with config {:?}",
-            simple_config.clone()
-        )
-    })?;
+    let snapshot = get_snapshot(simple_config.ignore_monitors && simple_config.ignore_workspaces)
+        .context("Failed to get hyprland data")?;
+    let (clients_data, active) =
+        collect_data_from(simple_config.clone(), snapshot).with_context(|| {
+            format!(
+                "Failed to collect data with config {:?}",
+                simple_config.clone()
+            )
+        })?;
 
     let (latest, send, receive) = share.deref();
     {
@@ -69,8 +86,12 @@ pub(crate) fn init(
 
         lock.active = active;
         lock.simple_config = simple_config.clone();
+        lock.launcher_config = LauncherConfig::new(&gui_config, &lock.init_config, &submap_config);
         lock.gui_config = gui_config.clone();
         lock.hypr_data = clients_data;
+        emit(Event::Opened {
+            selected: lock.active.clone(),
+        });
         drop(lock);
     }
 
@@ -118,12 +139,22 @@ pub(crate) fn close(share: &Share, kill: bool, client_id: u8) -> anyhow::Result<
         if let Some(selected) = lock.launcher_config.selected {
             if let Some(exec) = lock.launcher_config.execs.get(selected) {
                 show_launch_spawn(share.clone(), Some(client_id));
-                run_program(&exec.exec, &exec.path, exec.terminal);
-                cache_run(&exec.exec).warn("Failed to cache run");
+                run_program(
+                    exec,
+                    lock.launcher_config.default_terminal.as_deref(),
+                    lock.init_config.launch_backend,
+                    selected_workspace(lock.active.as_ref(), &lock.hypr_data).as_deref(),
+                );
+                record_launch(&exec.id).warn("Failed to record launch");
+                emit(Event::Launched {
+                    name: exec.name.to_string(),
+                    exec: exec.exec.to_string(),
+                });
             } else {
                 warn!("Selected program (nr. {}) not found, killing", selected);
             }
             drop(lock); // drop lock after both ifs
+            emit(Event::Closed { selected: None });
         } else {
             drop(lock); // drop lock before sending hide
 
@@ -139,6 +170,9 @@ pub(crate) fn close(share: &Share, kill: bool, client_id: u8) -> anyhow::Result<
             // (KeyboardMode::Exclusive on launcher doesn't allow switching windows if it is still active)
             let lock = latest.lock().expect("Failed to lock");
             switch_to_active(lock.active.as_ref(), &lock.hypr_data)?;
+            emit(Event::Closed {
+                selected: lock.active.clone(),
+            });
             drop(lock);
         }
     } else {
@@ -151,9 +185,158 @@ pub(crate) fn close(share: &Share, kill: bool, client_id: u8) -> anyhow::Result<
             .recv_blocking()
             .context("Unable to receive GUI update")?;
         trace!("Received hide finish from GUI: {rec:?}");
+        emit(Event::Closed { selected: None });
     }
 
-    clear_recent_clients();
     reload_desktop_maps();
     Ok(())
 }
+
+/// reloads the config file the daemon was started with and restarts the GUI to apply it
+pub(crate) fn reload(share: &Share) -> anyhow::Result<()> {
+    let (latest, send, receive) = share.deref();
+    let init_config = latest.lock().expect("Failed to lock").init_config.clone();
+    let path = init_config
+        .config_file
+        .as_ref()
+        .context("Daemon wasn't started with --config")?;
+    let new_config = load_init_config(path, &init_config)
+        .with_context(|| format!("Failed to load config at {path:?}"))?;
+    info!("Reloaded config {new_config:?}");
+    if new_config.register_binds {
+        register_binds(&new_config.binds).context("Failed to register binds")?;
+    }
+    crate::set_disable_toasts(new_config.disable_toasts);
+    latest.lock().expect("Failed to lock").init_config = new_config;
+
+    // windows, css and launcher are only created on start of the GUI
+    trace!("Sending exit to GUI");
+    send.send_blocking((GUISend::Exit, UpdateCause::BackgroundThread(None)))
+        .context("Unable to exit the GUI")?;
+    let rec = receive
+        .recv_blocking()
+        .context("Unable to receive GUI update")?;
+    trace!("Received exit finish from GUI: {rec:?}");
+    Ok(())
+}
+
+#[cfg(feature = "config")]
+fn load_init_config(path: &Path, init_config: &InitConfig) -> anyhow::Result<InitConfig> {
+    crate::config::load_init_config(path, init_config)
+}
+
+#[cfg(not(feature = "config"))]
+fn load_init_config(_path: &Path, _init_config: &InitConfig) -> anyhow::Result<InitConfig> {
+    anyhow::bail!("hyprswitch was built without the config feature")
+}
+
+pub(crate) fn query(share: &Share, open: bool) -> QueryData {
+    let (latest, _, _) = share.deref();
+    let lock = latest.lock().expect("Failed to lock");
+
+    let clients = lock
+        .hypr_data
+        .clients
+        .iter()
+        .filter(|(_, c)| c.enabled)
+        .collect::<Vec<_>>();
+    let workspaces = lock
+        .hypr_data
+        .workspaces
+        .iter()
+        .filter(|(_, w)| w.enabled)
+        .collect::<Vec<_>>();
+    let monitors = lock
+        .hypr_data
+        .monitors
+        .iter()
+        .filter(|(_, m)| m.enabled)
+        .collect::<Vec<_>>();
+
+    // offsets are only calculated for the current switch type (only these can be dispatched to)
+    let switch_type = &lock.simple_config.switch_type;
+    let client_offset = |index: usize| {
+        matches!(switch_type, SwitchType::Client).then(|| {
+            let selected = match &lock.active {
+                Some(Active::Client(addr)) => clients.iter().position(|(a, _)| a == addr),
+                _ => None,
+            };
+            offset(index, selected, clients.len())
+        })
+    };
+    let workspace_offset = |index: usize| {
+        matches!(switch_type, SwitchType::Workspace).then(|| {
+            let selected = match &lock.active {
+                Some(Active::Workspace(wid)) => workspaces.iter().position(|(id, _)| id == wid),
+                _ => None,
+            };
+            offset(index, selected, workspaces.len())
+        })
+    };
+    let monitor_offset = |index: usize| {
+        matches!(switch_type, SwitchType::Monitor).then(|| {
+            let selected = match &lock.active {
+                Some(Active::Monitor(mid)) => monitors.iter().position(|(id, _)| id == mid),
+                _ => None,
+            };
+            offset(index, selected, monitors.len())
+        })
+    };
+
+    QueryData {
+        open,
+        simple_config: lock.simple_config.clone(),
+        gui_config: lock.gui_config.clone(),
+        selected: lock.active.clone(),
+        clients: clients
+            .iter()
+            .enumerate()
+            .map(|(index, (address, client))| QueryClient {
+                address: address.clone(),
+                class: client.class.clone(),
+                title: client.title.clone(),
+                workspace: client.workspace,
+                monitor: client.monitor,
+                offset: client_offset(index),
+            })
+            .collect(),
+        workspaces: workspaces
+            .iter()
+            .enumerate()
+            .map(|(index, (id, workspace))| QueryWorkspace {
+                id: *id,
+                name: workspace.name.clone(),
+                monitor: workspace.monitor,
+                offset: workspace_offset(index),
+            })
+            .collect(),
+        monitors: monitors
+            .iter()
+            .enumerate()
+            .map(|(index, (id, monitor))| QueryMonitor {
+                id: *id,
+                connector: monitor.connector.clone(),
+                offset: monitor_offset(index),
+            })
+            .collect(),
+        launcher: QueryLauncher {
+            selected: lock.launcher_config.selected,
+            matches: lock
+                .launcher_config
+                .execs
+                .iter()
+                .map(|e| (e.name.to_string(), e.exec.to_string()))
+                .collect(),
+        },
+    }
+}
+
+/// forward offset from the selected item to the item at index (same as `dispatch --offset`)
+///
+/// if nothing is selected the first dispatch selects the item at offset - 1
+fn offset(index: usize, selected: Option<usize>, len: usize) -> usize {
+    match selected {
+        Some(selected) => (index + len - selected) % len,
+        None => index + 1,
+    }
+}
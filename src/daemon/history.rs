@@ -0,0 +1,409 @@
+use crate::Warn;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+const VERSION: u32 = 1;
+// the score of a launch halves every week
+const HALF_LIFE_SECS: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+// entries of applications that are no longer installed are removed if they weren't launched for 30 days
+const PRUNE_AFTER_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// launches of the applications, keyed by desktop file ID (e.g. `firefox.desktop`)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    pub entries: BTreeMap<String, HistoryEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub launches: u64,
+    /// unix timestamp in seconds
+    pub last_launch: u64,
+    // score at the time of the last launch, decays over time
+    score: f64,
+}
+
+impl HistoryEntry {
+    /// the score decayed to `now`, every launch adds 1 which halves every [`HALF_LIFE_SECS`]
+    pub fn frecency(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last_launch) as f64;
+        self.score * 0.5f64.powf(age / HALF_LIFE_SECS)
+    }
+}
+
+impl History {
+    /// reads the history at `path`, an empty history if the file doesn't exist
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read history file {path:?}"))?;
+        let history: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse history file {path:?}"))?;
+        if history.version > VERSION {
+            warn!(
+                "History file {path:?} has a newer version ({}) than supported ({VERSION})",
+                history.version
+            );
+        }
+        Ok(history)
+    }
+
+    /// writes the history to a temporary file and renames it to `path`, so the file is never partially written
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let dir = path
+            .parent()
+            .with_context(|| format!("History file {path:?} has no parent directory"))?;
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {dir:?}"))?;
+
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let content = serde_json::to_string(&History {
+            version: VERSION,
+            entries: self.entries.clone(),
+        })
+        .context("Failed to serialize history")?;
+        let mut file = fs::File::create(&tmp_path)
+            .with_context(|| format!("Failed to create file {tmp_path:?}"))?;
+        file.write_all(content.as_bytes())
+            .and_then(|_| file.sync_all())
+            .with_context(|| format!("Failed to write file {tmp_path:?}"))?;
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to move {tmp_path:?} to {path:?}"))?;
+        Ok(())
+    }
+
+    pub fn launch(&mut self, id: &str, now: u64) {
+        let entry = self.entries.entry(id.to_string()).or_insert(HistoryEntry {
+            launches: 0,
+            last_launch: now,
+            score: 0.0,
+        });
+        entry.score = entry.frecency(now) + 1.0;
+        entry.launches += 1;
+        entry.last_launch = entry.last_launch.max(now);
+    }
+
+    /// adds the launches of the old run cache (keyed by `Exec`) as if they happened `now`, returns the number of imported entries
+    pub fn import_run_cache(
+        &mut self,
+        content: &str,
+        id_by_exec: impl Fn(&str) -> Option<Box<str>>,
+        now: u64,
+    ) -> anyhow::Result<usize> {
+        let counts: HashMap<String, u64> =
+            serde_json::from_str(content).context("Failed to parse run cache")?;
+        let mut imported = 0;
+        for (exec, launches) in counts {
+            let Some(id) = id_by_exec(exec.trim()) else {
+                debug!("No desktop file found for {exec:?} of the run cache");
+                continue;
+            };
+            let entry = self.entries.entry(id.to_string()).or_insert(HistoryEntry {
+                launches: 0,
+                last_launch: now,
+                score: 0.0,
+            });
+            entry.score = entry.frecency(now) + launches as f64;
+            entry.launches += launches;
+            entry.last_launch = entry.last_launch.max(now);
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    /// removes entries of applications that aren't `installed` and weren't launched for [`PRUNE_AFTER_SECS`], returns the number of removed entries
+    pub fn prune(&mut self, installed: &HashSet<&str>, now: u64) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|id, entry| {
+            installed.contains(id.as_str())
+                || now.saturating_sub(entry.last_launch) < PRUNE_AFTER_SECS
+        });
+        before - self.entries.len()
+    }
+}
+
+/// history of the daemon, loaded on first use
+fn get_history() -> &'static Mutex<Option<History>> {
+    static HISTORY: OnceLock<Mutex<Option<History>>> = OnceLock::new();
+    HISTORY.get_or_init(|| Mutex::new(None))
+}
+
+/// held while the history is written in the background, so the history isn't locked while saving
+fn get_save_lock() -> &'static Mutex<()> {
+    static SAVE: OnceLock<Mutex<()>> = OnceLock::new();
+    SAVE.get_or_init(|| Mutex::new(()))
+}
+
+fn with_history<T>(f: impl FnOnce(&mut History, &Path) -> T) -> anyhow::Result<T> {
+    let path = get_path().context("Failed to get history path")?;
+    let mut lock = get_history().lock().expect("Failed to lock history");
+    let history = match lock.as_mut() {
+        Some(history) => history,
+        None => lock.insert(load_or_move_aside(&path)?),
+    };
+    Ok(f(history, &path))
+}
+
+/// an unreadable history is moved to `<path>.bak` before starting with an empty one, so it isn't overwritten by the next save
+fn load_or_move_aside(path: &Path) -> anyhow::Result<History> {
+    History::load(path).or_else(|e| {
+        let backup =
+            move_aside(path).context("Not using the history, it can't be read or moved")?;
+        warn!("{e:?}, moved it to {backup:?} and starting with an empty history");
+        Ok(History::default())
+    })
+}
+
+/// renames the file at `path` to `<path>.bak`
+fn move_aside(path: &Path) -> anyhow::Result<PathBuf> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    fs::rename(path, &backup).with_context(|| format!("Failed to move {path:?} to {backup:?}"))?;
+    Ok(backup)
+}
+
+/// records a launch of the desktop file with the `id`, the history is saved in the background
+/// (launches are recorded on the GTK main loop)
+pub fn record_launch(id: &str) -> anyhow::Result<()> {
+    with_history(|history, _| history.launch(id, now()))?;
+    thread::spawn(|| save_history().warn("Failed to save history"));
+    Ok(())
+}
+
+/// saves the history of the daemon, it is only locked while it's copied
+fn save_history() -> anyhow::Result<()> {
+    // the history is copied after waiting for other saves, so the file isn't overwritten by an older one
+    let _saving = get_save_lock().lock().expect("Failed to lock history save");
+    let (history, path) = with_history(|history, path| (history.clone(), path.to_path_buf()))?;
+    history.save(&path)
+}
+
+/// current frecency of every application in the history (empty if the history can't be read)
+pub fn get_frecencies() -> HashMap<String, f64> {
+    let now = now();
+    with_history(|history, _| {
+        history
+            .entries
+            .iter()
+            .map(|(id, entry)| (id.clone(), entry.frecency(now)))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// removes old entries of uninstalled applications, the history is only saved (in the background) if entries were removed
+pub fn prune_history(installed: &HashSet<&str>) -> anyhow::Result<()> {
+    let removed = with_history(|history, _| history.prune(installed, now()))?;
+    if removed > 0 {
+        debug!("Removed {removed} entries of uninstalled applications from the history");
+        thread::spawn(|| save_history().warn("Failed to save history"));
+    }
+    Ok(())
+}
+
+/// moves the launch counts of the old `run_cache.json` (or `HYPRSWITCH_CACHE_FILE`) into the history and removes it
+pub fn migrate_run_cache(id_by_exec: impl Fn(&str) -> Option<Box<str>>) -> anyhow::Result<()> {
+    let Some(cache_path) = get_run_cache_path().filter(|p| p.exists()) else {
+        return Ok(());
+    };
+    let content = fs::read_to_string(&cache_path)
+        .with_context(|| format!("Failed to read run cache {cache_path:?}"))?;
+    let imported =
+        match with_history(|history, _| history.import_run_cache(&content, id_by_exec, now()))? {
+            Ok(imported) => imported,
+            Err(e) => {
+                // else the import is tried again after every reload of the desktop files
+                let backup = move_aside(&cache_path)?;
+                return Err(e.context(format!("Moved the run cache to {backup:?}")));
+            }
+        };
+    save_history()?;
+    fs::remove_file(&cache_path)
+        .with_context(|| format!("Failed to remove run cache {cache_path:?}"))?;
+    info!("Migrated {imported} entries of the run cache {cache_path:?} to the history");
+    Ok(())
+}
+
+/// reads the history from disk (not the one loaded by the daemon)
+pub fn load_history() -> anyhow::Result<History> {
+    History::load(&get_path().context("Failed to get history path")?)
+}
+
+/// current unix timestamp in seconds
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn get_path() -> Option<PathBuf> {
+    env::var_os("HYPRSWITCH_HISTORY_FILE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            get_cache_dir().map(|mut path| {
+                path.push("hyprswitch/history.json");
+                path
+            })
+        })
+}
+
+/// file of the launch counts before the history was added
+fn get_run_cache_path() -> Option<PathBuf> {
+    env::var_os("HYPRSWITCH_CACHE_FILE")
+        .map(PathBuf::from)
+        .or_else(|| {
+            get_cache_dir().map(|mut path| {
+                path.push("hyprswitch/run_cache.json");
+                path
+            })
+        })
+}
+
+fn get_cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn launches_decay() {
+        let mut history = History::default();
+        history.launch("firefox.desktop", 0);
+        let entry = history.entries["firefox.desktop"];
+        assert_eq!(entry.frecency(0), 1.0);
+        assert!((entry.frecency(7 * DAY) - 0.5).abs() < 1e-9);
+        assert!((entry.frecency(14 * DAY) - 0.25).abs() < 1e-9);
+
+        // the previous launch decayed to 0.5
+        history.launch("firefox.desktop", 7 * DAY);
+        let entry = history.entries["firefox.desktop"];
+        assert_eq!(entry.launches, 2);
+        assert_eq!(entry.last_launch, 7 * DAY);
+        assert!((entry.frecency(7 * DAY) - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn recent_launches_rank_higher() {
+        let mut history = History::default();
+        for _ in 0..10 {
+            history.launch("old.desktop", 0);
+        }
+        history.launch("new.desktop", 60 * DAY);
+        history.launch("new.desktop", 60 * DAY);
+        let now = 60 * DAY;
+        assert!(
+            history.entries["new.desktop"].frecency(now)
+                > history.entries["old.desktop"].frecency(now)
+        );
+    }
+
+    #[test]
+    fn prunes_old_uninstalled() {
+        let mut history = History::default();
+        history.launch("installed.desktop", 0);
+        history.launch("removed.desktop", 0);
+        history.launch("recent.desktop", 50 * DAY);
+        let installed = HashSet::from(["installed.desktop"]);
+
+        assert_eq!(history.prune(&installed, 20 * DAY), 0);
+        assert_eq!(history.prune(&installed, 60 * DAY), 1);
+        assert_eq!(
+            history.entries.keys().collect::<Vec<_>>(),
+            vec!["installed.desktop", "recent.desktop"]
+        );
+    }
+
+    #[test]
+    fn imports_run_cache() {
+        let mut history = History::default();
+        history.launch("firefox.desktop", 0);
+        let id_by_exec = |exec: &str| match exec {
+            "firefox" => Some(Box::from("firefox.desktop")),
+            "alacritty" => Some(Box::from("Alacritty.desktop")),
+            _ => None,
+        };
+        let imported = history
+            .import_run_cache(
+                r#"{"firefox ":3,"alacritty":2,"removed":5}"#,
+                id_by_exec,
+                7 * DAY,
+            )
+            .expect("Failed to import");
+        assert_eq!(imported, 2);
+        let firefox = history.entries["firefox.desktop"];
+        assert_eq!(firefox.launches, 4);
+        assert!((firefox.frecency(7 * DAY) - 3.5).abs() < 1e-9);
+        assert_eq!(history.entries["Alacritty.desktop"].launches, 2);
+        assert!(!history.entries.contains_key("removed"));
+
+        assert!(history.import_run_cache("not json", id_by_exec, 0).is_err());
+    }
+
+    #[test]
+    fn unreadable_history_is_moved_aside() {
+        let dir = env::temp_dir().join(format!("hyprswitch-history-bak-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("Failed to create test dir");
+        let path = dir.join("history.json");
+        fs::write(&path, "not json").expect("Failed to write");
+
+        let history = load_or_move_aside(&path).expect("Failed to load");
+        assert!(history.entries.is_empty());
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("history.json.bak")).expect("No backup"),
+            "not json"
+        );
+        fs::remove_dir_all(dir).expect("Failed to remove test dir");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = env::temp_dir().join(format!("hyprswitch-history-{}", std::process::id()));
+        let path = dir.join("history.json");
+        assert!(History::load(&path)
+            .expect("Failed to load")
+            .entries
+            .is_empty());
+
+        let mut history = History::default();
+        history.launch("firefox.desktop", 100);
+        history.launch("firefox.desktop", 200);
+        history.save(&path).expect("Failed to save");
+        assert!(!dir.join("history.json.tmp").exists());
+
+        let loaded = History::load(&path).expect("Failed to load");
+        assert_eq!(loaded.version, VERSION);
+        let (saved, loaded) = (
+            history.entries["firefox.desktop"],
+            loaded.entries["firefox.desktop"],
+        );
+        assert_eq!(loaded.launches, 2);
+        assert_eq!(loaded.last_launch, 200);
+        // floats aren't parsed exactly by serde_json
+        assert!((loaded.frecency(200) - saved.frecency(200)).abs() < 1e-9);
+        fs::remove_dir_all(dir).expect("Failed to remove test dir");
+    }
+}
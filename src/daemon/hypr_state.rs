@@ -0,0 +1,524 @@
+use crate::handle::{
+    client_history_push, client_history_remove, fetch_snapshot, monitor_history_push,
+    monitor_history_remove, workspace_history_push, workspace_history_remove, HyprlandSnapshot,
+};
+use crate::Warn;
+use hyprland::data::{
+    Client, Clients, FullscreenMode, Monitor, Monitors, Workspace, WorkspaceBasic, Workspaces,
+};
+use hyprland::event_listener::EventListener;
+use hyprland::prelude::{HyprData, HyprDataActiveOptional};
+use hyprland::shared::{Address, WorkspaceId};
+use std::sync::{Condvar, Mutex, OnceLock};
+use tracing::{debug, span, trace, warn, Level};
+
+/// parts of the cached state that changed since they were fetched
+#[derive(Debug, Default, Clone, Copy)]
+struct Outdated {
+    workspaces: bool,
+    monitors: bool,
+    active: bool,
+    // the clients can't be updated from the events (opened window, monitor changes, ...)
+    clients: bool,
+    // the clients were retiled, everything except their positions and sizes is up to date
+    positions: bool,
+}
+
+impl Outdated {
+    const NONE: Self = Self {
+        workspaces: false,
+        monitors: false,
+        active: false,
+        clients: false,
+        positions: false,
+    };
+    const ALL: Self = Self {
+        workspaces: true,
+        monitors: true,
+        active: true,
+        clients: true,
+        positions: true,
+    };
+    const WORKSPACES: Self = Self {
+        workspaces: true,
+        ..Self::NONE
+    };
+    const MONITORS: Self = Self {
+        monitors: true,
+        ..Self::NONE
+    };
+    const ACTIVE: Self = Self {
+        active: true,
+        ..Self::NONE
+    };
+    const CLIENTS: Self = Self {
+        clients: true,
+        ..Self::NONE
+    };
+    const POSITIONS: Self = Self {
+        positions: true,
+        ..Self::NONE
+    };
+
+    fn any(self) -> bool {
+        self.workspaces || self.monitors || self.active || self.clients || self.positions
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            workspaces: self.workspaces || other.workspaces,
+            monitors: self.monitors || other.monitors,
+            active: self.active || other.active,
+            clients: self.clients || other.clients,
+            positions: self.positions || other.positions,
+        }
+    }
+}
+
+/// the snapshot without the event listener
+#[derive(Debug, Default, Clone)]
+struct CachedState {
+    clients: Vec<Client>,
+    workspaces: Vec<Workspace>,
+    monitors: Vec<Monitor>,
+    active: Option<Client>,
+}
+
+impl CachedState {
+    /// the client with this address and the active client if it is the same
+    fn clients_mut<'a>(&'a mut self, address: &'a Address) -> impl Iterator<Item = &'a mut Client> {
+        self.clients
+            .iter_mut()
+            .chain(self.active.iter_mut())
+            .filter(move |c| c.address == *address)
+    }
+
+    /// applies `f` to the client, returns false if the client isn't cached
+    fn update_client(&mut self, address: &Address, mut f: impl FnMut(&mut Client)) -> bool {
+        let mut found = false;
+        for client in self.clients_mut(address) {
+            f(client);
+            found = true;
+        }
+        found
+    }
+
+    fn remove_client(&mut self, address: &Address) -> bool {
+        let Some(index) = self.clients.iter().position(|c| c.address == *address) else {
+            return false;
+        };
+        let removed = self.clients.remove(index);
+        if removed.focus_history_id >= 0 {
+            for client in &mut self.clients {
+                if client.focus_history_id > removed.focus_history_id {
+                    client.focus_history_id -= 1;
+                }
+            }
+        }
+        if self.active.as_ref().is_some_and(|a| a.address == *address) {
+            self.active = None;
+        }
+        true
+    }
+
+    /// moves the client to the front of the focus history and makes it the active client
+    fn focus_client(&mut self, address: Option<&Address>) -> bool {
+        let Some(address) = address else {
+            self.active = None;
+            return true;
+        };
+        let Some(focused) = self.clients.iter().find(|c| c.address == *address) else {
+            return false;
+        };
+        let previous = focused.focus_history_id;
+        for client in &mut self.clients {
+            if client.address == *address {
+                client.focus_history_id = 0;
+            } else if client.focus_history_id >= 0
+                && (previous < 0 || client.focus_history_id < previous)
+            {
+                client.focus_history_id += 1;
+            }
+        }
+        self.active = self.clients.iter().find(|c| c.address == *address).cloned();
+        true
+    }
+
+    /// moves the client to the workspace and the monitor of the workspace
+    fn move_client(&mut self, address: &Address, id: WorkspaceId, name: String) -> bool {
+        let Some(monitor) = self
+            .workspaces
+            .iter()
+            .find(|w| w.id == id)
+            .map(|w| w.monitor_id)
+        else {
+            return false;
+        };
+        self.update_client(address, |c| {
+            c.workspace = WorkspaceBasic {
+                id,
+                name: name.clone(),
+            };
+            c.monitor = monitor;
+        })
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) -> bool {
+        let Some(address) = self.active.as_ref().map(|a| a.address.clone()) else {
+            return false;
+        };
+        self.update_client(&address, |c| {
+            // the exact mode is fetched with the positions
+            c.fullscreen = if fullscreen {
+                FullscreenMode::Fullscreen
+            } else {
+                FullscreenMode::None
+            };
+        })
+    }
+}
+
+#[derive(Default)]
+struct StateCache {
+    // false if the event listener isn't running (cache can't be trusted)
+    listening: bool,
+    outdated: Outdated,
+    // a thread is fetching the outdated parts, others wait for it instead of fetching too
+    refreshing: bool,
+    // incremented on every hyprland event that changes the state
+    events: u64,
+    state: CachedState,
+}
+
+fn get_cache() -> &'static (Mutex<StateCache>, Condvar) {
+    static CACHE: OnceLock<(Mutex<StateCache>, Condvar)> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        let cache = StateCache {
+            outdated: Outdated::ALL,
+            ..Default::default()
+        };
+        (Mutex::new(cache), Condvar::new())
+    })
+}
+
+/// returns the cached state, refreshes the outdated parts first
+///
+/// hyprland has no events for resizing or dragging windows and retiled positions are fetched in the background,
+/// `fresh_positions` fetches the clients again if their current positions are needed (one contiguous canvas)
+pub(super) fn get_snapshot(fresh_positions: bool) -> anyhow::Result<HyprlandSnapshot> {
+    if !get_cache()
+        .0
+        .lock()
+        .expect("Failed to lock state cache")
+        .listening
+    {
+        debug!("Not listening to hyprland events, fetching new snapshot");
+        return fetch_snapshot();
+    }
+    let state = if fresh_positions {
+        refresh(Outdated::POSITIONS, false)?
+    } else {
+        refresh(Outdated::NONE, true)?
+    };
+    Ok(HyprlandSnapshot {
+        clients: state.clients,
+        workspaces: state.workspaces,
+        monitors: state.monitors,
+        active: state.active,
+    })
+}
+
+/// fetches the outdated parts of the state (and the parts in `force`), waits for the fetch of another thread instead of fetching the same again
+///
+/// `defer_positions` leaves outdated positions to the background refresh if the clients are up to date otherwise
+fn refresh(force: Outdated, defer_positions: bool) -> anyhow::Result<CachedState> {
+    let (lock, cond) = get_cache();
+    let mut cache = cond
+        .wait_while(lock.lock().expect("Failed to lock state cache"), |c| {
+            c.refreshing
+        })
+        .expect("Failed to lock state cache");
+    let mut outdated = std::mem::take(&mut cache.outdated).merge(force);
+    if defer_positions && outdated.positions && !outdated.clients {
+        outdated.positions = false;
+        cache.outdated = Outdated::POSITIONS;
+    }
+    if !outdated.any() {
+        trace!("Using cached state");
+        return Ok(cache.state.clone());
+    }
+    cache.refreshing = true;
+    let mut state = cache.state.clone();
+    drop(cache);
+
+    trace!("Refreshing outdated state {outdated:?}");
+    let result = fetch(&mut state, outdated);
+    let mut cache = lock.lock().expect("Failed to lock state cache");
+    cache.refreshing = false;
+    match result {
+        Ok(()) => cache.state = state.clone(),
+        // fetched again by the next refresh
+        Err(_) => cache.outdated = cache.outdated.merge(outdated),
+    }
+    drop(cache);
+    cond.notify_all();
+    result.map(|_| state)
+}
+
+fn fetch(state: &mut CachedState, outdated: Outdated) -> anyhow::Result<()> {
+    if outdated.clients || outdated.positions {
+        state.clients = Clients::get()?.into_iter().collect();
+    }
+    if outdated.workspaces {
+        state.workspaces = Workspaces::get()?.into_iter().collect();
+    }
+    if outdated.monitors {
+        state.monitors = Monitors::get()?.into_iter().collect();
+    }
+    if outdated.active {
+        state.active = Client::get_active()?;
+    }
+    Ok(())
+}
+
+fn invalidate(outdated: Outdated) {
+    update(outdated, |_| true);
+}
+
+/// applies an event to the cached state and marks the parts in `outdated` to be fetched again
+///
+/// the clients are fetched again if `apply` returns false (client or workspace not cached)
+fn update(outdated: Outdated, apply: impl FnOnce(&mut CachedState) -> bool) {
+    let (lock, cond) = get_cache();
+    let mut cache = lock.lock().expect("Failed to lock state cache");
+    let mut outdated = cache.outdated.merge(outdated);
+    if !apply(&mut cache.state) {
+        outdated = outdated.merge(Outdated::CLIENTS);
+    }
+    // a running refresh replaces the state with one fetched before this event
+    if cache.refreshing {
+        outdated = outdated.merge(Outdated::CLIENTS).merge(Outdated::ACTIVE);
+    }
+    cache.outdated = outdated;
+    cache.events += 1;
+    drop(cache);
+    cond.notify_all();
+}
+
+fn set_listening(listening: bool) {
+    let mut cache = get_cache().0.lock().expect("Failed to lock state cache");
+    cache.listening = listening;
+}
+
+/// refreshes the outdated parts of the state in the background after every event
+fn refresh_blocking() {
+    let (lock, cond) = get_cache();
+    loop {
+        let events = {
+            let cache = cond
+                .wait_while(lock.lock().expect("Failed to lock state cache"), |c| {
+                    !c.outdated.any()
+                })
+                .expect("Failed to lock state cache");
+            cache.events
+        };
+        if let Err(e) = refresh(Outdated::NONE, false) {
+            warn!("Failed to refresh state: {e:?}");
+            // wait for the next event instead of retrying immediately
+            let cache = lock.lock().expect("Failed to lock state cache");
+            drop(
+                cond.wait_while(cache, |c| c.events == events)
+                    .expect("Failed to lock state cache"),
+            );
+        }
+    }
+}
+
+/// keeps the cached state and the focus histories up to date with the hyprland events
+///
+/// window events update the cached clients directly, events that retile the clients also fetch their positions in the background
+pub(super) fn start_state_listener() {
+    std::thread::spawn(|| {
+        let _span = span!(Level::TRACE, "state_refresh").entered();
+        refresh_blocking();
+    });
+
+    let mut event_listener = EventListener::new();
+    // openwindow doesn't contain the pid, size, etc. of the client
+    event_listener.add_window_opened_handler(|_| invalidate(Outdated::CLIENTS));
+    event_listener.add_window_closed_handler(|address| {
+        client_history_remove(&address);
+        update(Outdated::POSITIONS, |s| s.remove_client(&address));
+    });
+    event_listener.add_window_moved_handler(|data| {
+        update(Outdated::POSITIONS, |s| {
+            s.move_client(
+                &data.window_address,
+                data.workspace_id,
+                data.workspace_name.to_string(),
+            )
+        })
+    });
+    event_listener.add_window_title_changed_handler(|data| {
+        update(Outdated::NONE, |s| {
+            s.update_client(&data.address, |c| c.title.clone_from(&data.title))
+        })
+    });
+    event_listener.add_float_state_changed_handler(|data| {
+        update(Outdated::POSITIONS, |s| {
+            s.update_client(&data.address, |c| c.floating = data.floating)
+        })
+    });
+    event_listener.add_window_pinned_handler(|data| {
+        update(Outdated::NONE, |s| {
+            s.update_client(&data.address, |c| c.pinned = data.pinned)
+        })
+    });
+    event_listener.add_fullscreen_state_changed_handler(|fullscreen| {
+        update(Outdated::POSITIONS, |s| s.set_fullscreen(fullscreen))
+    });
+    event_listener.add_active_window_changed_handler(|data| {
+        // activewindowv2, also contains focus changes by mouse or other programs
+        if let Some(data) = &data {
+            client_history_push(data.address.clone());
+        }
+        update(Outdated::NONE, |s| {
+            s.focus_client(data.as_ref().map(|d| &d.address))
+        });
+    });
+    event_listener.add_workspace_changed_handler(|data| {
+        workspace_history_push(data.id);
+        // active workspace of the monitor
+        invalidate(Outdated::MONITORS.merge(Outdated::ACTIVE));
+    });
+    event_listener.add_active_monitor_changed_handler(|data| {
+        monitor_history_push(data.monitor_name);
+        invalidate(Outdated::MONITORS.merge(Outdated::ACTIVE));
+    });
+    event_listener.add_workspace_added_handler(|_| invalidate(Outdated::WORKSPACES));
+    event_listener.add_workspace_deleted_handler(|data| {
+        workspace_history_remove(&data.id);
+        invalidate(Outdated::WORKSPACES);
+    });
+    // clients on the workspace change their monitor or workspace name
+    event_listener.add_workspace_moved_handler(|_| {
+        invalidate(
+            Outdated::WORKSPACES
+                .merge(Outdated::MONITORS)
+                .merge(Outdated::CLIENTS),
+        )
+    });
+    event_listener.add_workspace_renamed_handler(|_| {
+        invalidate(Outdated::WORKSPACES.merge(Outdated::CLIENTS))
+    });
+    event_listener.add_changed_special_handler(|_| {
+        invalidate(Outdated::WORKSPACES.merge(Outdated::MONITORS))
+    });
+    event_listener.add_special_removed_handler(|_| {
+        invalidate(Outdated::WORKSPACES.merge(Outdated::MONITORS))
+    });
+    event_listener.add_monitor_added_handler(|_| invalidate(Outdated::ALL));
+    event_listener.add_monitor_removed_handler(|data| {
+        monitor_history_remove(&data);
+        invalidate(Outdated::ALL);
+    });
+    event_listener.add_config_reloaded_handler(|| invalidate(Outdated::ALL));
+
+    set_listening(true);
+    // refetch everything that happened before the listener was connected
+    invalidate(Outdated::ALL);
+    event_listener
+        .start_listener()
+        .warn("Failed to start hyprland state listener");
+    debug!("Hyprland state listener stopped, not using the cached state anymore");
+    set_listening(false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(address: &str, workspace: WorkspaceId, focus_history_id: i8) -> Client {
+        Client {
+            address: Address::new(address),
+            at: (0, 0),
+            size: (100, 100),
+            workspace: WorkspaceBasic {
+                id: workspace,
+                name: workspace.to_string(),
+            },
+            floating: false,
+            fullscreen: FullscreenMode::None,
+            fullscreen_client: FullscreenMode::None,
+            monitor: 0,
+            initial_class: String::new(),
+            class: String::new(),
+            initial_title: String::new(),
+            title: String::new(),
+            pid: 0,
+            xwayland: false,
+            pinned: false,
+            grouped: vec![],
+            mapped: true,
+            swallowing: None,
+            focus_history_id,
+        }
+    }
+
+    fn cached() -> CachedState {
+        let clients = vec![client("a", 1, 0), client("b", 1, 1), client("c", 2, 2)];
+        CachedState {
+            active: Some(clients[0].clone()),
+            clients,
+            ..Default::default()
+        }
+    }
+
+    fn focus_history(state: &CachedState) -> Vec<i8> {
+        state.clients.iter().map(|c| c.focus_history_id).collect()
+    }
+
+    #[test]
+    fn focus_moves_client_to_front() {
+        let mut state = cached();
+        assert!(state.focus_client(Some(&Address::new("c"))));
+        assert_eq!(focus_history(&state), vec![1, 2, 0]);
+        assert_eq!(state.active.map(|a| a.address), Some(Address::new("c")));
+
+        let mut state = cached();
+        assert!(state.focus_client(None));
+        assert!(state.active.is_none());
+        // not cached yet (just opened)
+        assert!(!state.focus_client(Some(&Address::new("d"))));
+    }
+
+    #[test]
+    fn remove_closes_focus_history_gap() {
+        let mut state = cached();
+        assert!(state.remove_client(&Address::new("a")));
+        assert_eq!(focus_history(&state), vec![0, 1]);
+        assert!(state.active.is_none());
+        assert!(!state.remove_client(&Address::new("a")));
+    }
+
+    #[test]
+    fn update_changes_active_client_too() {
+        let mut state = cached();
+        assert!(state.update_client(&Address::new("a"), |c| c.title = "title".into()));
+        assert_eq!(state.clients[0].title, "title");
+        assert_eq!(
+            state.active.as_ref().map(|a| a.title.as_str()),
+            Some("title")
+        );
+        assert!(state.set_fullscreen(true));
+        assert_eq!(state.clients[0].fullscreen, FullscreenMode::Fullscreen);
+        assert!(!state.update_client(&Address::new("d"), |c| c.floating = true));
+    }
+
+    #[test]
+    fn move_needs_cached_workspace() {
+        let mut state = cached();
+        assert!(!state.move_client(&Address::new("b"), 2, "2".into()));
+        assert_eq!(state.clients[1].workspace.id, 1);
+    }
+}
@@ -1,16 +1,27 @@
+use std::process::exit;
 use std::sync::{Arc, Mutex};
 
 use crate::{InitConfig, Payload, Share, SharedData};
+#[cfg(feature = "config")]
+use anyhow::Context;
+use gtk4::glib;
 use gtk4::glib::clone;
-use tracing::{debug, span, Level};
+use tracing::{debug, info, span, Level};
 
-mod cache;
+mod binds;
+#[cfg(feature = "config")]
+mod config_watch;
 pub mod gui;
 mod handle_client;
 mod handle_fns;
+mod history;
+mod hypr_state;
+mod ordered;
 mod submap;
+mod subscribe;
 
-use crate::envs::SYSTEMD_SERVICE;
+use crate::envs::get_envs;
+pub use binds::unregister_binds;
 pub use submap::deactivate_submap;
 
 pub fn start_daemon(init_config: InitConfig) -> anyhow::Result<()> {
@@ -18,7 +29,33 @@ pub fn start_daemon(init_config: InitConfig) -> anyhow::Result<()> {
     // create arc to send to threads containing the config the daemon was initialized with and the data (clients, etc.)
     let (sender, receiver) = async_channel::bounded::<Payload>(1);
     let (return_sender, return_receiver) = async_channel::bounded::<Option<Payload>>(1);
-    let share: Share = Arc::new((Mutex::new(SharedData::default()), sender, return_receiver));
+    #[cfg(feature = "config")]
+    let init_config = match &init_config.config_file {
+        Some(path) => crate::config::load_init_config(path, &init_config)
+            .with_context(|| format!("Failed to load config at {path:?}"))?,
+        None => init_config,
+    };
+    crate::set_disable_toasts(init_config.disable_toasts);
+    #[cfg(feature = "config")]
+    let config_file = init_config.config_file.clone();
+    if init_config.register_binds {
+        // remove the registered binds when stopped (handled in the GUI main loop)
+        for signal in [libc::SIGINT, libc::SIGTERM] {
+            glib::unix_signal_add(signal, move || {
+                info!("Received signal {signal}, removing binds and exiting");
+                unregister_binds();
+                exit(0)
+            });
+        }
+    }
+    let share: Share = Arc::new((
+        Mutex::new(SharedData {
+            init_config,
+            ..Default::default()
+        }),
+        sender,
+        return_receiver,
+    ));
 
     std::thread::scope(move |scope| {
         scope.spawn(clone!(
@@ -30,7 +67,24 @@ pub fn start_daemon(init_config: InitConfig) -> anyhow::Result<()> {
             }
         ));
 
-        if *SYSTEMD_SERVICE {
+        scope.spawn(move || {
+            let _span = span!(Level::TRACE, "hypr_state").entered();
+            hypr_state::start_state_listener();
+        });
+
+        #[cfg(feature = "config")]
+        if let Some(path) = config_file {
+            scope.spawn(clone!(
+                #[strong]
+                share,
+                move || {
+                    let _span = span!(Level::TRACE, "config_watch").entered();
+                    config_watch::start_config_watcher_blocking(&share, &path);
+                }
+            ));
+        }
+
+        if get_envs().systemd_service {
             scope.spawn(clone!(
                 #[strong]
                 share,
@@ -48,9 +102,11 @@ pub fn start_daemon(init_config: InitConfig) -> anyhow::Result<()> {
                 // restart gui if this loop exits
                 let _span = span!(Level::TRACE, "gui").entered();
                 gui::reload_desktop_maps();
+                // settings might have been changed by a reload
+                let init_config = share.0.lock().expect("Failed to lock").init_config.clone();
                 gui::start_gui_blocking(
                     share.clone(),
-                    init_config.clone(),
+                    init_config,
                     receiver.clone(),
                     return_sender.clone(),
                 );
@@ -0,0 +1,58 @@
+use std::collections::BTreeSet;
+use std::sync::{Condvar, Mutex, OnceLock};
+
+#[derive(Default)]
+struct State {
+    // next ticket to issue
+    issued: u64,
+    // all tickets before this one are finished
+    current: u64,
+    // finished tickets after current (finished out of order)
+    finished: BTreeSet<u64>,
+}
+
+fn get_state() -> &'static (Mutex<State>, Condvar) {
+    static STATE: OnceLock<(Mutex<State>, Condvar)> = OnceLock::new();
+    STATE.get_or_init(|| (Mutex::new(State::default()), Condvar::new()))
+}
+
+/// position in the order in which state transitions are applied (order in which clients connected / sent messages)
+///
+/// dropping the ticket without running lets later tickets continue (e.g. client sent nothing or read-only command)
+#[derive(Debug)]
+pub(super) struct Ticket(u64);
+
+impl Ticket {
+    pub(super) fn new() -> Self {
+        let mut state = get_state().0.lock().expect("Failed to lock order");
+        let ticket = Ticket(state.issued);
+        state.issued += 1;
+        ticket
+    }
+
+    /// waits until all earlier tickets are finished and runs `f`
+    pub(super) fn run<T>(self, f: impl FnOnce() -> T) -> T {
+        let (lock, cond) = get_state();
+        let state = cond
+            .wait_while(lock.lock().expect("Failed to lock order"), |s| {
+                s.current != self.0
+            })
+            .expect("Failed to lock order");
+        drop(state); // don't hold the lock while running, only the turn
+        f()
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let (lock, cond) = get_state();
+        let mut state = lock.lock().expect("Failed to lock order");
+        state.finished.insert(self.0);
+        while state.finished.first() == Some(&state.current) {
+            state.finished.pop_first();
+            state.current += 1;
+        }
+        drop(state);
+        cond.notify_all();
+    }
+}
@@ -0,0 +1,116 @@
+use crate::Event;
+use anyhow::Context;
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, trace, warn};
+
+// slow subscribers get dropped instead of blocking the daemon
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+fn get_subscribers() -> &'static Mutex<Vec<UnixStream>> {
+    static SUBSCRIBERS: OnceLock<Mutex<Vec<UnixStream>>> = OnceLock::new();
+    SUBSCRIBERS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+pub(super) fn add_subscriber(stream: UnixStream) -> anyhow::Result<()> {
+    stream
+        .set_write_timeout(Some(WRITE_TIMEOUT))
+        .context("Failed to set write timeout")?;
+    let mut subscribers = get_subscribers()
+        .lock()
+        .expect("Failed to lock subscribers");
+    subscribers.push(stream);
+    debug!("Added subscriber ({} subscribed)", subscribers.len());
+    Ok(())
+}
+
+/// events are written to the subscribers by a thread, so slow subscribers never block the daemon or the GUI
+fn get_sender() -> &'static Sender<Event> {
+    static SENDER: OnceLock<Sender<Event>> = OnceLock::new();
+    SENDER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Event>();
+        thread::spawn(move || {
+            for event in receiver {
+                write_event(&event);
+            }
+        });
+        sender
+    })
+}
+
+/// queues the event for all subscribers, doesn't block
+pub(crate) fn emit(event: Event) {
+    if let Err(e) = get_sender().send(event) {
+        warn!("Failed to queue event: {e}");
+    }
+}
+
+/// sends the event to all subscribers, removes subscribers that closed the stream
+fn write_event(event: &Event) {
+    let mut subscribers = get_subscribers()
+        .lock()
+        .expect("Failed to lock subscribers");
+    if subscribers.is_empty() {
+        return;
+    }
+    trace!(
+        "Emitting event {event:?} to {} subscribers",
+        subscribers.len()
+    );
+    let mut line = serde_json::to_vec(event).expect("Failed to serialize event");
+    line.push(b'\n');
+    subscribers.retain_mut(|stream| match stream.write_all(&line) {
+        Ok(_) => true,
+        Err(e) => {
+            debug!("Removing subscriber: {e}");
+            false
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::time::Instant;
+
+    #[test]
+    fn stalled_subscribers_dont_block_emit() {
+        // never reads and its buffer is full, so every write to it times out
+        let (stalled, _stalled_peer) = UnixStream::pair().expect("Failed to create stream");
+        stalled
+            .set_nonblocking(true)
+            .expect("Failed to set nonblocking");
+        while (&stalled).write(&[0; 4096]).is_ok() {}
+        stalled
+            .set_nonblocking(false)
+            .expect("Failed to set blocking");
+        let (subscriber, reader) = UnixStream::pair().expect("Failed to create stream");
+        add_subscriber(stalled).expect("Failed to add subscriber");
+        add_subscriber(subscriber).expect("Failed to add subscriber");
+
+        let start = Instant::now();
+        for i in 0..10 {
+            emit(Event::Closed {
+                selected: Some(crate::Active::Workspace(i)),
+            });
+        }
+        assert!(start.elapsed() < WRITE_TIMEOUT);
+
+        let mut lines = BufReader::new(reader).lines();
+        for i in 0..10 {
+            let line = lines
+                .next()
+                .expect("Stream closed")
+                .expect("Failed to read");
+            assert_eq!(
+                line,
+                format!(r#"{{"Closed":{{"selected":{{"Workspace":{i}}}}}}}"#)
+            );
+        }
+    }
+}
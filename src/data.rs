@@ -23,16 +23,19 @@ pub struct WorkspaceData {
 
 #[derive(Debug, Clone)]
 pub struct ClientData {
-    pub x: i16,
-    pub y: i16,
-    pub width: i16,
-    pub height: i16,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
     pub class: String,
     pub title: String,
     pub workspace: WorkspaceId,
     pub monitor: MonitorId,
     pub focus_history_id: i8,
     pub floating: bool,
+    pub fullscreen: bool,
+    pub pinned: bool,
+    pub xwayland: bool,
     pub enabled: bool,
     pub pid: i32,
 }
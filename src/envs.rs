@@ -1,52 +1,148 @@
-use lazy_static::lazy_static;
+use crate::InitOverrides;
 use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::OnceLock;
 use tracing::debug;
 
-lazy_static! {
-    pub static ref SHOW_LAUNCHER: bool = env::var("SHOW_LAUNCHER")
-        .map(|s| s.parse().expect("Failed to parse SHOW_LAUNCHER"))
-        .unwrap_or(false);
-    pub static ref LAUNCHER_MAX_ITEMS: usize = env::var("LAUNCHER_MAX_ITEMS")
-        .map(|s| {
-            let value = s.parse().expect("Failed to parse LAUNCHER_MAX_ITEMS");
-            if !(1..=10).contains(&value) {
-                panic!("LAUNCHER_MAX_ITEMS must be between 1 and 10");
-            }
-            value
-        })
-        .unwrap_or(5);
-    pub static ref DEFAULT_TERMINAL: Option<String> =
-        env::var("DEFAULT_TERMINAL").map_or(None, |s| Some(s.to_string()));
+/// settings read from environment variables
+#[derive(Debug, Default, Clone)]
+pub struct Envs {
+    // settings of the daemon, unset variables are None (the CLI takes precedence over these)
+    pub overrides: InitOverrides,
 
     // not implemented in config generator
-    pub static ref LOG_MODULE_PATH: bool = env::var("LOG_MODULE_PATH")
-        .map(|s| s.parse().expect("Failed to parse LOG_MODULE_PATH"))
-        .unwrap_or(false);
+    pub log_module_path: bool,
     // allows exiting on new monitors or new versions as it automatically restarts
-    pub static ref SYSTEMD_SERVICE: bool = env::var("SYSTEMD_SERVICE")
-        .map(|s| s.parse().expect("Failed to parse SYSTEMD_SERVICE"))
-        .unwrap_or(false);
-
-    pub static ref REMOVE_HTML_FROM_WORKSPACE_NAME: bool = env::var("REMOVE_HTML_FROM_WORKSPACE_NAME")
-        .map(|s| s.parse().expect("Failed to parse REMOVE_HTML_FROM_WORKSPACE_NAME"))
-        .unwrap_or(true);
-    pub static ref DISABLE_TOASTS: bool = env::var("DISABLE_TOASTS")
-        .map(|s| s.parse().expect("Failed to parse DISABLE_TOASTS"))
-        .unwrap_or(false);
-    pub static ref SHOW_LAUNCHER_EXECS: bool = env::var("SHOW_LAUNCHER_EXECS")
-        .map(|s| s.parse().expect("Failed to parse SHOW_LAUNCHER_EXECS"))
-        .unwrap_or(true);
-    pub static ref LAUNCHER_ANIMATE_LAUNCH_TIME: u64 = env::var("LAUNCHER_ANIMATE_LAUNCH_TIME")
-        .map(|s| s.parse().expect("Failed to parse LAUNCHER_ANIMATE_LAUNCH_TIME"))
-        .unwrap_or(300);
+    pub systemd_service: bool,
+}
+
+static ENVS: OnceLock<Envs> = OnceLock::new();
+
+/// reads the env variables once at startup, all invalid values are reported together
+pub fn init_envs() -> anyhow::Result<()> {
+    let envs = parse_envs(|name| env::var(name).ok())?;
+    ENVS.set(envs)
+        .map_err(|_| anyhow::anyhow!("Env variables already initialized"))
+}
+
+/// the env variables read by [`init_envs`] (defaults if not initialized)
+pub fn get_envs() -> &'static Envs {
+    ENVS.get_or_init(Envs::default)
+}
+
+fn parse_envs(var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Envs> {
+    let mut errors = Vec::new();
+    let envs = Envs {
+        overrides: InitOverrides {
+            show_launcher: parse(&var, "SHOW_LAUNCHER", &mut errors),
+            launcher_max_items: parse(&var, "LAUNCHER_MAX_ITEMS", &mut errors),
+            show_launcher_execs: parse(&var, "SHOW_LAUNCHER_EXECS", &mut errors),
+            launcher_animate_launch_time: parse(&var, "LAUNCHER_ANIMATE_LAUNCH_TIME", &mut errors),
+            default_terminal: var("DEFAULT_TERMINAL").filter(|s| !s.is_empty()),
+            remove_html_from_workspace_name: parse(
+                &var,
+                "REMOVE_HTML_FROM_WORKSPACE_NAME",
+                &mut errors,
+            ),
+            icon_size: parse(&var, "ICON_SIZE", &mut errors),
+            show_default_icon: parse(&var, "SHOW_DEFAULT_ICON", &mut errors),
+            launch_backend: parse(&var, "LAUNCH_BACKEND", &mut errors),
+            disable_toasts: parse(&var, "DISABLE_TOASTS", &mut errors),
+            ..Default::default()
+        },
+        log_module_path: parse(&var, "LOG_MODULE_PATH", &mut errors).unwrap_or(false),
+        systemd_service: parse(&var, "SYSTEMD_SERVICE", &mut errors).unwrap_or(false),
+    };
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "Found {} invalid env variables:\n{}",
+            errors.len(),
+            errors.join("\n")
+        );
+    }
+    Ok(envs)
+}
+
+fn parse<T: FromStr>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    errors: &mut Vec<String>,
+) -> Option<T>
+where
+    T::Err: Display,
+{
+    let value = var(name)?;
+    value
+        .parse()
+        .map_err(|e| errors.push(format!("- {name}={value:?}: {e}")))
+        .ok()
 }
 
 pub fn envvar_dump() {
-    debug!("ENV dump: \
-    SHOW_LAUNCHER: {:?}, LAUNCHER_MAX_ITEMS: {:?}, \
-    DEFAULT_TERMINAL: {:?}, LOG_MODULE_PATH: {:?}, REMOVE_HTML_FROM_WORKSPACE_NAME: {:?}, DISABLE_TOASTS: {:?}, \
-    SHOW_LAUNCHER_EXECS: {:?}, LAUNCHER_ANIMATE_LAUNCH_TIME: {:?}, SYSTEMD_SERVICE: {:?}",
-        *SHOW_LAUNCHER, *LAUNCHER_MAX_ITEMS,
-        *DEFAULT_TERMINAL, *LOG_MODULE_PATH, *REMOVE_HTML_FROM_WORKSPACE_NAME, *DISABLE_TOASTS,
-        *SHOW_LAUNCHER_EXECS, *LAUNCHER_ANIMATE_LAUNCH_TIME, *SYSTEMD_SERVICE);
+    debug!("ENV dump: {:?}", get_envs());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_envs;
+    use crate::LaunchBackend;
+
+    fn envs(vars: &[(&str, &str)]) -> anyhow::Result<super::Envs> {
+        parse_envs(|name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn unset_envs_are_none() {
+        let envs = envs(&[]).expect("Failed to parse envs");
+        assert_eq!(envs.overrides.launcher_max_items, None);
+        assert_eq!(envs.overrides.default_terminal, None);
+        assert!(!envs.systemd_service);
+    }
+
+    #[test]
+    fn parses_envs() {
+        let envs = envs(&[
+            ("LAUNCHER_MAX_ITEMS", "8"),
+            ("SHOW_DEFAULT_ICON", "true"),
+            ("DEFAULT_TERMINAL", "alacritty"),
+            ("SYSTEMD_SERVICE", "true"),
+            ("LAUNCH_BACKEND", "fork"),
+        ])
+        .expect("Failed to parse envs");
+        assert_eq!(envs.overrides.launcher_max_items, Some(8));
+        assert_eq!(envs.overrides.show_default_icon, Some(true));
+        assert_eq!(
+            envs.overrides.default_terminal.as_deref(),
+            Some("alacritty")
+        );
+        assert!(envs.systemd_service);
+        assert_eq!(envs.overrides.launch_backend, Some(LaunchBackend::Fork));
+    }
+
+    #[test]
+    fn reports_all_invalid_envs() {
+        let error = envs(&[("LAUNCHER_MAX_ITEMS", "many"), ("DISABLE_TOASTS", "yes")])
+            .expect_err("Invalid envs were parsed");
+        assert_eq!(
+            error.to_string(),
+            "Found 2 invalid env variables:\n\
+            - LAUNCHER_MAX_ITEMS=\"many\": invalid digit found in string\n\
+            - DISABLE_TOASTS=\"yes\": provided string was not `true` or `false`"
+        );
+    }
+
+    #[test]
+    fn invalid_launch_backend() {
+        let error = envs(&[("LAUNCH_BACKEND", "exec")]).expect_err("Invalid env was parsed");
+        assert_eq!(
+            error.to_string(),
+            "Found 1 invalid env variables:\n\
+            - LAUNCH_BACKEND=\"exec\": expected auto, systemd, fork or hyprland, found \"exec\""
+        );
+    }
 }
@@ -1,33 +1,63 @@
-use crate::handle::get_recent_clients_map;
-use crate::handle::sort::{sort_clients, update_clients};
+use crate::handle::filter::compile_filters;
+use crate::handle::history::{get_client_history, get_monitor_history, get_workspace_history};
+use crate::handle::sort::{sort_clients, update_clients, update_clients_canvas};
 use crate::{Active, ClientData, HyprlandData, MonitorData, SwitchType, WorkspaceData};
 use crate::{FindByFirst, SimpleConfig};
-use hyprland::data::{Client, Clients, Monitors, Workspaces};
+use anyhow::Context;
+use hyprland::data::{Client, Clients, FullscreenMode, Monitor, Monitors, Workspace, Workspaces};
 use hyprland::prelude::{HyprData, HyprDataActiveOptional};
 use hyprland::shared::{Address, MonitorId, WorkspaceId};
 use tracing::{span, trace, warn, Level};
 
 // type Active = (Option<Address>, Option<WorkspaceId>, Option<MonitorId>);
 
+/// raw data from hyprland, processed by [`collect_data_from`]
+#[derive(Debug, Clone)]
+pub struct HyprlandSnapshot {
+    pub clients: Vec<Client>,
+    pub workspaces: Vec<Workspace>,
+    pub monitors: Vec<Monitor>,
+    pub active: Option<Client>,
+}
+
+pub fn fetch_snapshot() -> anyhow::Result<HyprlandSnapshot> {
+    let _span = span!(Level::TRACE, "fetch_snapshot").entered();
+    Ok(HyprlandSnapshot {
+        clients: Clients::get()?.into_iter().collect(),
+        workspaces: Workspaces::get()?.into_iter().collect(),
+        monitors: Monitors::get()?.into_iter().collect(),
+        active: Client::get_active()?,
+    })
+}
+
 pub fn collect_data(config: SimpleConfig) -> anyhow::Result<(HyprlandData, Option<Active>)> {
+    collect_data_from(config, fetch_snapshot()?)
+}
+
+pub fn collect_data_from(
+    config: SimpleConfig,
+    snapshot: HyprlandSnapshot,
+) -> anyhow::Result<(HyprlandData, Option<Active>)> {
     let _span = span!(Level::TRACE, "collect_data").entered();
-    let clients = Clients::get()?
+    let clients = snapshot
+        .clients
         .into_iter()
         .filter(|c| c.workspace.id != -1) // ignore clients on invalid workspaces
         .filter(|w| config.include_special_workspaces || !w.workspace.id < 0)
         .collect::<Vec<_>>();
 
-    let monitors = Monitors::get()?;
+    let monitors = snapshot.monitors;
 
     // sort and filter all workspaces sorted by ID
     let workspaces = {
-        let mut workspaces = Workspaces::get()?
+        let mut workspaces = snapshot
+            .workspaces
             .into_iter()
             .filter(|w| w.id != -1) // filter invalid workspaces
             .filter(|w| config.include_special_workspaces || !w.id < 0)
             .collect::<Vec<_>>();
 
-        workspaces.sort_by(|a, b| a.id.cmp(&b.id));
+        workspaces.sort_by_key(|a| a.id);
         workspaces
     };
 
@@ -88,16 +118,22 @@ pub fn collect_data(config: SimpleConfig) -> anyhow::Result<(HyprlandData, Optio
                 cd.push((
                     client.address.clone(),
                     ClientData {
-                        x: client.at.0,
-                        y: client.at.1,
-                        width: client.size.0,
-                        height: client.size.1,
+                        x: client.at.0 as i32,
+                        y: client.at.1 as i32,
+                        width: client.size.0 as i32,
+                        height: client.size.1 as i32,
                         class: client.class.clone(),
                         workspace: client.workspace.id,
                         monitor: client.monitor,
                         focus_history_id: client.focus_history_id,
                         title: client.title.clone(),
                         floating: client.floating,
+                        fullscreen: matches!(
+                            client.fullscreen,
+                            FullscreenMode::Fullscreen | FullscreenMode::MaximizedFullscreen
+                        ),
+                        pinned: client.pinned,
+                        xwayland: client.xwayland,
                         pid: client.pid,
                         enabled: false, // gets updated later
                     },
@@ -112,36 +148,29 @@ pub fn collect_data(config: SimpleConfig) -> anyhow::Result<(HyprlandData, Optio
         cd
     };
 
-    if config.ignore_monitors {
+    if config.ignore_monitors && config.ignore_workspaces {
+        client_data = update_clients(client_data, Some(&workspace_data), Some(&monitor_data));
+        client_data = update_clients_canvas(client_data, &workspace_data, &monitor_data, false);
+    } else if config.ignore_monitors {
         client_data = update_clients(client_data, Some(&workspace_data), None);
     } else {
         client_data = update_clients(client_data, Some(&workspace_data), Some(&monitor_data));
     }
 
     if config.sort_recent {
-        let mut focus_map = get_recent_clients_map()
-            .lock()
-            .expect("Failed to lock focus_map");
-        if focus_map.is_empty() {
-            focus_map.extend(
-                client_data
-                    .iter()
-                    .map(|(address, client_data)| (address.clone(), client_data.focus_history_id)),
-            );
+        let mut history = get_client_history();
+        if history.is_empty() {
+            // seed with the focus history of hyprland (daemon just started or running without daemon)
+            let mut seed = client_data
+                .iter()
+                .map(|(address, client_data)| (client_data.focus_history_id, address.clone()))
+                .collect::<Vec<_>>();
+            seed.sort_by_key(|(focus_history_id, _)| *focus_history_id);
+            history.extend(seed.into_iter().map(|(_, address)| address));
         };
-        client_data.sort_by(|(a_addr, a), (b_addr, b)| {
-            let a_focus_id = focus_map.get(a_addr);
-            let b_focus_id = focus_map.get(b_addr);
-            if a_focus_id.is_none() && b_focus_id.is_none() {
-                a.focus_history_id.cmp(&b.focus_history_id) // both none -> sort by focus_history_id
-            } else if a_focus_id.is_none() {
-                std::cmp::Ordering::Greater
-            } else if b_focus_id.is_none() {
-                std::cmp::Ordering::Less
-            } else {
-                #[allow(clippy::unnecessary_unwrap)]
-                a_focus_id.unwrap().cmp(b_focus_id.unwrap())
-            }
+        // clients not in the history (never focused since the daemon started) are sorted by focus_history_id
+        client_data.sort_by_key(|(address, client_data)| {
+            (history.rank(address), client_data.focus_history_id)
         });
     } else {
         client_data = sort_clients(
@@ -150,15 +179,48 @@ pub fn collect_data(config: SimpleConfig) -> anyhow::Result<(HyprlandData, Optio
             config.ignore_monitors,
         );
     }
-    // also remove offset of monitors (else gui will be offset)
-    if config.ignore_monitors {
+    // also remove offset of monitors / canvas (else gui will be offset)
+    if config.ignore_monitors && config.ignore_workspaces {
+        client_data = update_clients_canvas(client_data, &workspace_data, &monitor_data, true);
+    } else if config.ignore_monitors {
         client_data = update_clients(client_data, None, Some(&monitor_data));
     }
 
-    workspace_data.sort_by(|a, b| a.0.cmp(&b.0));
-    monitor_data.sort_by(|a, b| a.0.cmp(&b.0));
+    if config.sort_recent {
+        // seed with the focused workspace / monitor and the ones of the recently focused clients
+        // (daemon just started or running without daemon), else switching to the previous one wouldn't work
+        let focused = monitors.iter().find(|m| m.focused);
+        let mut history = get_workspace_history();
+        if history.is_empty() {
+            history.extend(recent_first(
+                focused.map(|m| m.active_workspace.id),
+                client_data
+                    .iter()
+                    .map(|(_, c)| (c.focus_history_id, c.workspace)),
+            ));
+        }
+        // workspaces / monitors never focused since the daemon started are sorted by ID
+        workspace_data.sort_by_key(|(id, _)| (history.rank(id), *id));
+        drop(history);
+        let mut history = get_monitor_history();
+        if history.is_empty() {
+            history.extend(recent_first(
+                focused.map(|m| m.name.clone()),
+                client_data.iter().filter_map(|(_, c)| {
+                    monitor_data
+                        .find_by_first(&c.monitor)
+                        .map(|m| (c.focus_history_id, m.connector.clone()))
+                }),
+            ));
+        }
+        monitor_data.sort_by_key(|(id, monitor)| (history.rank(&monitor.connector), *id));
+        drop(history);
+    } else {
+        workspace_data.sort_by_key(|a| a.0);
+        monitor_data.sort_by_key(|a| a.0);
+    }
 
-    let active = Client::get_active()?;
+    let active = snapshot.active;
     let active: Option<(String, WorkspaceId, MonitorId, Address)> = active.as_ref().map_or_else(
         || None,
         |a| {
@@ -173,19 +235,21 @@ pub fn collect_data(config: SimpleConfig) -> anyhow::Result<(HyprlandData, Optio
 
     trace!("active: {:?}", active);
 
+    let filters = compile_filters(&config.filters).context("Failed to compile filters")?;
     for (_, client) in client_data.iter_mut() {
-        client.enabled = (!config.filter_same_class
-            || active
-                .as_ref()
-                .map_or(true, |active| client.class == *active.0))
+        client.enabled = filters.iter().all(|f| f.matches(client))
+            && (!config.filter_same_class
+                || active
+                    .as_ref()
+                    .is_none_or(|active| client.class == *active.0))
             && (!config.filter_current_workspace
                 || active
                     .as_ref()
-                    .map_or(true, |active| client.workspace == active.1))
+                    .is_none_or(|active| client.workspace == active.1))
             && (!config.filter_current_monitor
                 || active
                     .as_ref()
-                    .map_or(true, |active| client.monitor == active.2));
+                    .is_none_or(|active| client.monitor == active.2));
     }
 
     // iterate over all workspaces and set active to false if no client is on the workspace is active
@@ -221,3 +285,37 @@ pub fn collect_data(config: SimpleConfig) -> anyhow::Result<(HyprlandData, Optio
         active,
     ))
 }
+
+/// `first` followed by the items of the clients in the order they were focused (by focus_history_id), without duplicates
+fn recent_first<T: PartialEq>(first: Option<T>, clients: impl Iterator<Item = (i8, T)>) -> Vec<T> {
+    let mut clients = clients
+        .filter(|(focus_history_id, _)| *focus_history_id >= 0)
+        .collect::<Vec<_>>();
+    clients.sort_by_key(|(focus_history_id, _)| *focus_history_id);
+    let mut items = Vec::new();
+    for item in first
+        .into_iter()
+        .chain(clients.into_iter().map(|(_, item)| item))
+    {
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_recent_first() {
+        // (focus_history_id, workspace) of the clients
+        let clients = [(2, 3), (0, 1), (1, 2), (3, 1), (-1, 4)];
+        assert_eq!(recent_first(Some(1), clients.into_iter()), vec![1, 2, 3]);
+        // focused workspace without clients
+        assert_eq!(recent_first(Some(5), clients.into_iter()), vec![5, 1, 2, 3]);
+        assert_eq!(recent_first(None, clients.into_iter()), vec![1, 2, 3]);
+        assert_eq!(recent_first::<i32>(Some(1), [].into_iter()), vec![1]);
+    }
+}
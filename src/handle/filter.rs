@@ -0,0 +1,133 @@
+use crate::{ClientData, Filter};
+use anyhow::Context;
+use regex::Regex;
+
+pub(crate) enum CompiledFilter {
+    Class(Regex),
+    Title(Regex),
+    ExcludeClass(Regex),
+    ExcludeTitle(Regex),
+    Floating(bool),
+    Fullscreen(bool),
+    Pinned(bool),
+    Xwayland(bool),
+}
+
+impl CompiledFilter {
+    pub(crate) fn matches(&self, client: &ClientData) -> bool {
+        match self {
+            CompiledFilter::Class(regex) => regex.is_match(&client.class),
+            CompiledFilter::Title(regex) => regex.is_match(&client.title),
+            CompiledFilter::ExcludeClass(regex) => !regex.is_match(&client.class),
+            CompiledFilter::ExcludeTitle(regex) => !regex.is_match(&client.title),
+            CompiledFilter::Floating(floating) => client.floating == *floating,
+            CompiledFilter::Fullscreen(fullscreen) => client.fullscreen == *fullscreen,
+            CompiledFilter::Pinned(pinned) => client.pinned == *pinned,
+            CompiledFilter::Xwayland(xwayland) => client.xwayland == *xwayland,
+        }
+    }
+}
+
+pub(crate) fn compile_filters(filters: &[Filter]) -> anyhow::Result<Vec<CompiledFilter>> {
+    filters
+        .iter()
+        .map(|filter| {
+            Ok(match filter {
+                Filter::Class(regex) => CompiledFilter::Class(compile(regex)?),
+                Filter::Title(regex) => CompiledFilter::Title(compile(regex)?),
+                Filter::ExcludeClass(regex) => CompiledFilter::ExcludeClass(compile(regex)?),
+                Filter::ExcludeTitle(regex) => CompiledFilter::ExcludeTitle(compile(regex)?),
+                Filter::Floating(floating) => CompiledFilter::Floating(*floating),
+                Filter::Fullscreen(fullscreen) => CompiledFilter::Fullscreen(*fullscreen),
+                Filter::Pinned(pinned) => CompiledFilter::Pinned(*pinned),
+                Filter::Xwayland(xwayland) => CompiledFilter::Xwayland(*xwayland),
+            })
+        })
+        .collect()
+}
+
+fn compile(regex: &str) -> anyhow::Result<Regex> {
+    Regex::new(regex).with_context(|| format!("Invalid regex {regex:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(class: &str, title: &str, floating: bool) -> ClientData {
+        ClientData {
+            x: 0,
+            y: 0,
+            width: 100,
+            height: 100,
+            class: class.to_string(),
+            title: title.to_string(),
+            workspace: 1,
+            monitor: 0,
+            focus_history_id: 0,
+            floating,
+            fullscreen: false,
+            pinned: false,
+            xwayland: false,
+            enabled: true,
+            pid: 0,
+        }
+    }
+
+    fn matches(filters: &[Filter], client: &ClientData) -> bool {
+        compile_filters(filters)
+            .expect("Failed to compile filters")
+            .iter()
+            .all(|f| f.matches(client))
+    }
+
+    #[test]
+    fn regex_filters() {
+        let firefox = client("firefox", "GitHub - Mozilla Firefox", false);
+        let kitty = client("kitty", "nvim", false);
+        let class = [Filter::Class("^(firefox|chromium)$".to_string())];
+        assert!(matches(&class, &firefox));
+        assert!(!matches(&class, &kitty));
+
+        let title = [Filter::Title("(?i)github".to_string())];
+        assert!(matches(&title, &firefox));
+        assert!(!matches(&title, &kitty));
+
+        let exclude = [
+            Filter::ExcludeClass("^kitty$".to_string()),
+            Filter::ExcludeTitle("YouTube".to_string()),
+        ];
+        assert!(matches(&exclude, &firefox));
+        assert!(!matches(&exclude, &kitty));
+        // not anchored
+        assert!(!matches(
+            &[Filter::ExcludeClass("fox".to_string())],
+            &firefox
+        ));
+    }
+
+    #[test]
+    fn state_filters() {
+        let floating = client("pavucontrol", "Volume Control", true);
+        let tiled = client("kitty", "nvim", false);
+        assert!(matches(&[Filter::Floating(true)], &floating));
+        assert!(!matches(&[Filter::Floating(true)], &tiled));
+        assert!(matches(&[Filter::Floating(false)], &tiled));
+        // all filters must match
+        let filters = [Filter::Floating(false), Filter::Class("kitty".to_string())];
+        assert!(matches(&filters, &tiled));
+        assert!(!matches(&filters, &floating));
+        assert!(matches(&[], &floating));
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let error = compile_filters(&[
+            Filter::Class("firefox".to_string()),
+            Filter::Title("(unclosed".to_string()),
+        ])
+        .err()
+        .expect("Invalid regex was compiled");
+        assert_eq!(error.to_string(), "Invalid regex \"(unclosed\"");
+    }
+}
@@ -0,0 +1,84 @@
+use hyprland::shared::{Address, WorkspaceId};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// most recently focused first, kept up to date by the daemon (survives closing the GUI)
+#[derive(Debug)]
+pub(crate) struct FocusHistory<T>(Vec<T>);
+
+impl<T: PartialEq> FocusHistory<T> {
+    const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// moves the item to the front of the history
+    fn push(&mut self, item: T) {
+        self.0.retain(|i| *i != item);
+        self.0.insert(0, item);
+    }
+
+    fn remove(&mut self, item: &T) {
+        self.0.retain(|i| i != item);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn extend(&mut self, items: impl IntoIterator<Item = T>) {
+        self.0.extend(items);
+    }
+
+    /// position in the history, items never focused are sorted after all focused items
+    pub(crate) fn rank(&self, item: &T) -> usize {
+        self.0.iter().position(|i| i == item).unwrap_or(usize::MAX)
+    }
+}
+
+pub(crate) fn get_client_history() -> MutexGuard<'static, FocusHistory<Address>> {
+    static HISTORY_LOCK: OnceLock<Mutex<FocusHistory<Address>>> = OnceLock::new();
+    HISTORY_LOCK
+        .get_or_init(|| Mutex::new(FocusHistory::new()))
+        .lock()
+        .expect("Failed to lock client_history")
+}
+
+pub(crate) fn get_workspace_history() -> MutexGuard<'static, FocusHistory<WorkspaceId>> {
+    static HISTORY_LOCK: OnceLock<Mutex<FocusHistory<WorkspaceId>>> = OnceLock::new();
+    HISTORY_LOCK
+        .get_or_init(|| Mutex::new(FocusHistory::new()))
+        .lock()
+        .expect("Failed to lock workspace_history")
+}
+
+/// monitors are tracked by connector (focusedmon only contains the name)
+pub(crate) fn get_monitor_history() -> MutexGuard<'static, FocusHistory<String>> {
+    static HISTORY_LOCK: OnceLock<Mutex<FocusHistory<String>>> = OnceLock::new();
+    HISTORY_LOCK
+        .get_or_init(|| Mutex::new(FocusHistory::new()))
+        .lock()
+        .expect("Failed to lock monitor_history")
+}
+
+pub fn client_history_push(address: Address) {
+    get_client_history().push(address);
+}
+
+pub fn client_history_remove(address: &Address) {
+    get_client_history().remove(address);
+}
+
+pub fn workspace_history_push(id: WorkspaceId) {
+    get_workspace_history().push(id);
+}
+
+pub fn workspace_history_remove(id: &WorkspaceId) {
+    get_workspace_history().remove(id);
+}
+
+pub fn monitor_history_push(connector: String) {
+    get_monitor_history().push(connector);
+}
+
+pub fn monitor_history_remove(connector: &String) {
+    get_monitor_history().remove(connector);
+}
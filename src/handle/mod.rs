@@ -1,25 +1,27 @@
-use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
-
 use anyhow::Context;
 use hyprland::data::{Client, Monitor, Monitors};
 use hyprland::prelude::{HyprData, HyprDataActiveOptional, HyprDataVec};
-use hyprland::shared::Address;
 use tracing::info;
 
-pub use data::collect_data;
+pub use data::{collect_data, collect_data_from, fetch_snapshot, HyprlandSnapshot};
 pub use exec::switch_to_active;
+pub use history::{
+    client_history_push, client_history_remove, monitor_history_push, monitor_history_remove,
+    workspace_history_push, workspace_history_remove,
+};
 
 use crate::handle::next::{find_next_client, find_next_monitor, find_next_workspace};
 use crate::{Active, DispatchConfig, HyprlandData, SwitchType};
 
 mod data;
 mod exec;
+mod filter;
+mod history;
 mod next;
 mod run;
 mod sort;
 
-pub use run::run_program;
+pub use run::{find_executable, run_program, selected_workspace};
 
 pub fn find_next(
     switch_type: &SwitchType,
@@ -73,18 +75,6 @@ pub fn find_next(
     }
 }
 
-fn get_recent_clients_map() -> &'static Mutex<HashMap<Address, i8>> {
-    static MAP_LOCK: OnceLock<Mutex<HashMap<Address, i8>>> = OnceLock::new();
-    MAP_LOCK.get_or_init(|| Mutex::new(HashMap::new()))
-}
-
-pub fn clear_recent_clients() {
-    get_recent_clients_map()
-        .lock()
-        .expect("Failed to lock focus_map")
-        .clear();
-}
-
 pub fn get_monitors() -> Vec<Monitor> {
     Monitors::get().map_or(vec![], |monitors| monitors.to_vec())
 }
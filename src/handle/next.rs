@@ -1,8 +1,7 @@
-use anyhow::Context;
 use hyprland::shared::{Address, MonitorId, WorkspaceId};
 use tracing::{trace, warn};
 
-use crate::{ClientData, DispatchConfig, MonitorData, WorkspaceData};
+use crate::{ClientData, DispatchConfig, MonitorData, ResponseError, SwitchType, WorkspaceData};
 
 pub(crate) fn find_next_monitor<'a>(
     dispatch_config: &DispatchConfig,
@@ -58,7 +57,7 @@ pub(crate) fn find_next_monitor<'a>(
         .iter()
         .cycle()
         .nth(index)
-        .context("No next monitor found")?;
+        .ok_or(ResponseError::NoNext(SwitchType::Monitor))?;
 
     Ok(*next_monitor)
 }
@@ -114,7 +113,7 @@ pub(crate) fn find_next_workspace<'a>(
         .iter()
         .cycle()
         .nth(index)
-        .context("No next client found")?;
+        .ok_or(ResponseError::NoNext(SwitchType::Workspace))?;
 
     Ok(*next_workspace)
 }
@@ -169,7 +168,7 @@ pub(crate) fn find_next_client<'a>(
         .iter()
         .cycle()
         .nth(index)
-        .context("No next client found")?;
+        .ok_or(ResponseError::NoNext(SwitchType::Client))?;
 
     Ok(*next_client)
 }
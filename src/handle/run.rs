@@ -1,59 +1,257 @@
-use crate::envs::DEFAULT_TERMINAL;
-use crate::Warn;
-use std::ops::Deref;
+use crate::{Active, Exec, FindByFirst, HyprlandData, LaunchBackend, Warn};
+use anyhow::Context;
+use hyprland::dispatch::{Dispatch, DispatchType};
+use std::env;
+use std::ffi::OsStr;
+use std::io;
+use std::os::unix::fs::PermissionsExt;
 use std::os::unix::prelude::CommandExt;
+use std::path::Path;
 use std::process::{Command, Stdio};
-use std::{io, thread};
-use tracing::{debug, info};
-
-pub fn run_program(run: &str, path: &Option<Box<str>>, terminal: bool) {
-    if terminal {
-        if let Some(term) = DEFAULT_TERMINAL.deref() {
-            let mut process = Command::new(term);
-            process.arg("-e");
-            run_command(&mut process, run, path).warn("Failed to run command");
-        } else {
-            info!("No default terminal found, trying to find one. (pass DEFAULT_TERMINAL to set a default terminal)");
-            for term in TERMINALS {
-                let mut process = Command::new(term);
-                process.arg("-e");
-                if run_command(&mut process, run, path).is_ok() {
-                    break;
-                }
-            }
+use std::thread;
+use tracing::{debug, info, warn};
+
+/// `workspace` is the workspace rule the program is opened on (only used with [`LaunchBackend::Hyprland`])
+pub fn run_program(
+    exec: &Exec,
+    default_terminal: Option<&str>,
+    backend: LaunchBackend,
+    workspace: Option<&str>,
+) {
+    let args = command_args(exec);
+    let args = if exec.terminal {
+        let term = default_terminal.or_else(|| {
+            info!("No default terminal found, trying to find one. (pass DEFAULT_TERMINAL or set default_terminal in the config to set a default terminal)");
+            TERMINALS
+                .into_iter()
+                .find(|term| find_executable(term, env::var_os("PATH").as_deref()))
+        });
+        let Some(term) = term else {
+            warn!("No terminal found to run {}", exec.name);
+            return;
+        };
+        [term, "-e"].into_iter().chain(args).collect()
+    } else {
+        args
+    };
+    match resolve_backend(backend) {
+        LaunchBackend::Hyprland => {
+            dispatch_exec(&args, exec, workspace).warn("Failed to run command")
         }
+        backend => run_command(&args, exec, backend).warn("Failed to run command"),
+    };
+}
+
+/// workspace of the `active` workspace (selected in the GUI) for the workspace rule of the exec dispatcher
+///
+/// special and named workspaces (negative IDs) are referenced by name (`special:name`, `name:name`)
+pub fn selected_workspace(active: Option<&Active>, hypr_data: &HyprlandData) -> Option<String> {
+    let Some(Active::Workspace(id)) = active else {
+        return None;
+    };
+    if *id < 0 {
+        hypr_data
+            .workspaces
+            .find_by_first(id)
+            .map(|workspace| workspace_by_name(&workspace.name))
     } else {
-        let mut process = Command::new("sh");
-        process.arg("-c");
-        run_command(&mut process, run, path).warn("Failed to run command");
+        Some(id.to_string())
     }
 }
 
-fn run_command(command: &mut Command, run: &str, path: &Option<Box<str>>) -> io::Result<()> {
-    command.arg::<&str>(run.as_ref());
-    command.process_group(0);
+/// names of special workspaces already start with `special`
+fn workspace_by_name(name: &str) -> String {
+    if name == "special" || name.starts_with("special:") {
+        name.to_string()
+    } else {
+        format!("name:{name}")
+    }
+}
 
+/// program and arguments of the exec, exec values that can't be split into arguments are run with `sh -c`
+fn command_args(exec: &Exec) -> Vec<&str> {
+    match &exec.command {
+        Some(command) if !command.is_empty() => command.iter().map(|arg| &**arg).collect(),
+        _ => vec!["sh", "-c", &exec.exec],
+    }
+}
+
+/// [`LaunchBackend::Auto`] uses systemd if the systemd user instance is running,
+/// [`LaunchBackend::Systemd`] falls back to fork if it isn't
+fn resolve_backend(backend: LaunchBackend) -> LaunchBackend {
+    match backend {
+        LaunchBackend::Auto | LaunchBackend::Systemd if systemd_available() => {
+            LaunchBackend::Systemd
+        }
+        LaunchBackend::Auto => LaunchBackend::Fork,
+        LaunchBackend::Systemd => {
+            warn!("systemd user instance or systemd-run not found, forking instead");
+            LaunchBackend::Fork
+        }
+        backend => backend,
+    }
+}
+
+/// same check as sd_booted() and the socket of the user instance
+fn systemd_available() -> bool {
+    Path::new("/run/systemd/system").is_dir()
+        && env::var_os("XDG_RUNTIME_DIR")
+            .is_some_and(|dir| Path::new(&dir).join("systemd/private").exists())
+        && find_executable("systemd-run", env::var_os("PATH").as_deref())
+}
+
+/// runs the program with the exec dispatcher of Hyprland, so exec rules apply
+fn dispatch_exec(args: &[&str], exec: &Exec, workspace: Option<&str>) -> anyhow::Result<()> {
+    let command = hyprland_exec(args, exec.path.as_deref(), workspace);
+    info!("Dispatching exec {command}");
+    Dispatch::call(DispatchType::Exec(&command)).context("Failed to dispatch exec")
+}
+
+/// `[workspace <workspace> silent] cd <path> && <args>`, Hyprland runs the command with `sh -c`
+fn hyprland_exec(args: &[&str], path: Option<&str>, workspace: Option<&str>) -> String {
+    let mut command = String::new();
+    if let Some(workspace) = workspace {
+        command.push_str(&format!("[workspace {workspace} silent] "));
+    }
     if let Some(path) = path {
-        command.current_dir(path.as_ref());
+        command.push_str(&format!("cd {} && ", shell_quote(path)));
+    }
+    let args = args.iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>();
+    command.push_str(&args.join(" "));
+    command
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+fn run_command(args: &[&str], exec: &Exec, backend: LaunchBackend) -> io::Result<()> {
+    if backend != LaunchBackend::Systemd {
+        return run_forked(args, exec.path.as_deref(), &exec.id);
+    }
+    let mut command = Command::new("systemd-run");
+    command
+        .args(["--user", "--scope", "--collect", "--quiet"])
+        .arg(format!("--unit={}", unit_name(&exec.id, rand::random())))
+        .arg(format!("--description={}", exec.name))
+        .arg("--")
+        .args(args);
+    if let Some(path) = &exec.path {
+        command.current_dir(&**path);
     }
+    command.stdin(Stdio::null());
     info!("Running command: {:?}", command);
-    let out = command
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()?;
-
-    // spawn a thread to wait for the output
-    thread::spawn(move || {
-        let output = out.wait_with_output();
-        if let Ok(output) = output {
-            if !output.stdout.is_empty() || !output.stderr.is_empty() {
-                debug!("Output: {:?}", output);
-            }
-        }
+
+    // the program is only forked if systemd-run couldn't be started, it is never run twice
+    if let Err(e) = spawn_reaped(command) {
+        warn!("Failed to run systemd-run ({e}), forking instead");
+        return run_forked(args, exec.path.as_deref(), &exec.id);
+    }
+    Ok(())
+}
+
+/// systemd-run is replaced by the program after creating the scope, so it is waited for in a thread
+fn spawn_reaped(mut command: Command) -> io::Result<()> {
+    let mut child = command.spawn()?;
+    thread::spawn(move || match child.wait() {
+        Ok(status) => debug!("Program in scope exited with {status}"),
+        Err(e) => warn!("Failed to wait for systemd-run: {e}"),
     });
     Ok(())
 }
 
+/// forks the program and detaches it from the daemon, the output goes to the journal (with systemd-cat) if it is available
+///
+/// the program stays in the cgroup of the daemon, so it is stopped with the daemon if that runs as a systemd service
+fn run_forked(args: &[&str], path: Option<&str>, id: &str) -> io::Result<()> {
+    let mut command = if journal_available() {
+        let mut command = Command::new("systemd-cat");
+        command
+            .arg(format!(
+                "--identifier={}",
+                id.strip_suffix(".desktop").unwrap_or(id)
+            ))
+            .arg("--")
+            .args(args);
+        command
+    } else {
+        let mut command = Command::new(args[0]);
+        command
+            .args(&args[1..])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        command
+    };
+    if let Some(path) = path {
+        command.current_dir(path);
+    }
+    command.stdin(Stdio::null());
+    // SAFETY: detach only calls async-signal-safe functions
+    unsafe {
+        command.pre_exec(detach);
+    }
+
+    info!("Running command: {:?}", command);
+    // the child exits right after forking the program, exec errors are still returned by spawn
+    let status = command.spawn()?.wait()?;
+    debug!("Detached program exited with {status}");
+    Ok(())
+}
+
+/// the journal socket of systemd and systemd-cat to connect the output of a program to it
+fn journal_available() -> bool {
+    Path::new("/run/systemd/journal/stdout").exists()
+        && find_executable("systemd-cat", env::var_os("PATH").as_deref())
+}
+
+/// runs in the child before exec, forks again so the program is reparented to init (or a subreaper) and
+/// isn't killed with the session of the daemon
+fn detach() -> io::Result<()> {
+    // SAFETY: setsid, fork and _exit are async-signal-safe
+    unsafe {
+        if libc::setsid() == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        match libc::fork() {
+            -1 => Err(io::Error::last_os_error()),
+            // the grandchild continues with exec
+            0 => Ok(()),
+            _ => libc::_exit(0),
+        }
+    }
+}
+
+/// `app-hyprswitch-<desktop file ID>-<random>.scope` like the launchers of desktop environments name their scopes,
+/// chars that aren't allowed in unit names are escaped like `systemd-escape` does
+fn unit_name(id: &str, random: u32) -> String {
+    let id = id.strip_suffix(".desktop").unwrap_or(id);
+    let mut escaped = String::with_capacity(id.len());
+    for (i, byte) in id.bytes().enumerate() {
+        match byte {
+            b'.' if i == 0 => escaped.push_str("\\x2e"),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b':' | b'_' | b'.' => {
+                escaped.push(byte as char)
+            }
+            _ => escaped.push_str(&format!("\\x{byte:02x}")),
+        }
+    }
+    format!("app-hyprswitch-{escaped}-{random:08x}.scope")
+}
+
+/// whether `name` is an executable file (path with a `/`) or an executable in one of the dirs of `path_var`
+pub fn find_executable(name: &str, path_var: Option<&OsStr>) -> bool {
+    let is_executable = |path: &Path| {
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    };
+    if name.contains('/') {
+        return is_executable(Path::new(name));
+    }
+    path_var.is_some_and(|path_var| {
+        env::split_paths(path_var).any(|dir| is_executable(&dir.join(name)))
+    })
+}
+
 // from https://github.com/i3/i3/blob/next/i3-sensible-terminal
 const TERMINALS: [&str; 29] = [
     "alacritty",
@@ -86,3 +284,67 @@ const TERMINALS: [&str; 29] = [
     "wezterm",
     "rio",
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_names() {
+        assert_eq!(
+            unit_name("firefox.desktop", 0x1a2b),
+            "app-hyprswitch-firefox-00001a2b.scope"
+        );
+        assert_eq!(
+            unit_name("org.gnome.Nautilus.desktop", 1),
+            "app-hyprswitch-org.gnome.Nautilus-00000001.scope"
+        );
+        assert_eq!(
+            unit_name("kde-konsole.desktop", 1),
+            "app-hyprswitch-kde\\x2dkonsole-00000001.scope"
+        );
+        assert_eq!(
+            unit_name("my app.desktop", 1),
+            "app-hyprswitch-my\\x20app-00000001.scope"
+        );
+    }
+
+    #[test]
+    fn hyprland_exec_rules() {
+        assert_eq!(
+            hyprland_exec(&["firefox", "--new-window"], None, None),
+            "'firefox' '--new-window'"
+        );
+        assert_eq!(
+            hyprland_exec(&["sh", "-c", "echo 'hi'"], Some("/tmp"), Some("3")),
+            r"[workspace 3 silent] cd '/tmp' && 'sh' '-c' 'echo '\''hi'\'''"
+        );
+        assert_eq!(
+            hyprland_exec(&["kitty"], None, Some("special:scratch")),
+            "[workspace special:scratch silent] 'kitty'"
+        );
+        assert_eq!(
+            hyprland_exec(&["kitty"], None, Some(&workspace_by_name("code"))),
+            "[workspace name:code silent] 'kitty'"
+        );
+        assert_eq!(workspace_by_name("special:scratch"), "special:scratch");
+        assert_eq!(workspace_by_name("special"), "special");
+    }
+
+    #[test]
+    fn legacy_execs_use_a_shell() {
+        let exec = |command: Option<Vec<Box<str>>>| Exec {
+            id: "app.desktop".into(),
+            name: "App".into(),
+            exec: "app ~/file".into(),
+            command,
+            path: None,
+            terminal: false,
+        };
+        assert_eq!(
+            command_args(&exec(Some(vec!["app".into(), "--new".into()]))),
+            vec!["app", "--new"]
+        );
+        assert_eq!(command_args(&exec(None)), vec!["sh", "-c", "app ~/file"]);
+    }
+}
@@ -1,5 +1,5 @@
 pub use sort::sort_clients;
-pub use update::update_clients;
+pub use update::{update_clients, update_clients_canvas};
 
 #[allow(clippy::module_inception)]
 mod sort;
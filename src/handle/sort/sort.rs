@@ -9,6 +9,8 @@ use crate::ClientData;
 /// * 'clients' - Vector of clients to sort
 /// * 'ignore_workspaces' - Don't split clients into workspaces (treat all clients on monitor as one workspace)
 /// * 'ignore_monitors' - Don't split clients into monitors (treat all clients as one monitor)
+///
+/// if both are set, all clients are sorted as one workspace (clients must be placed on one canvas with [`super::update_clients_canvas`] before)
 pub fn sort_clients(
     clients: Vec<(Address, ClientData)>,
     ignore_workspaces: bool,
@@ -18,11 +20,9 @@ pub fn sort_clients(
     let monitors: Vec<Vec<Vec<(Address, ClientData)>>> = match (ignore_workspaces, ignore_monitors)
     {
         (true, true) => {
-            panic!(
-                "Can't ignore workspaces and monitors at the same time (currently not implemented)"
-            );
             // one monitor with one workspace with every client
-            // vec![vec![clients]]
+            // (clients must be moved to one canvas before, see update_clients_canvas)
+            vec![vec![clients]]
         }
         (true, false) => {
             // workspace -> clients
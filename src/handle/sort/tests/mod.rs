@@ -152,7 +152,7 @@ pub fn create_svg(
     svg::save(filename.clone(), &svg).unwrap_or_else(|_| panic!("unable to save svg {filename}"));
 }
 
-/// (x, y, width, height, workspace, monitor) => <increment>: ClientData { x, y, width, height, workspace, monitor, address: Address::new(<increment>), focus_history_id: <increment>, class: "test".to_string(), title: "test".to_string(), floating: false, active: true }
+/// (x, y, width, height, workspace, monitor) => <increment>: ClientData { x, y, width, height, workspace, monitor, address: Address::new(<increment>), focus_history_id: <increment>, class: "test".to_string(), title: "test".to_string(), floating: false, fullscreen: false, pinned: false, xwayland: false, active: true }
 ///
 /// ```rust
 /// let clients = client_vec![
@@ -183,6 +183,9 @@ macro_rules! client_vec {
                 class: "test".to_string(),
                 title: "test".to_string(),
                 floating: false,
+                fullscreen: false,
+                pinned: false,
+                xwayland: false,
                 enabled: true,
                 pid: 0,
             }));
@@ -1,9 +1,10 @@
+use hyprland::shared::{Address, MonitorId};
 use std::time::Instant;
 
 use crate::handle::sort::tests::{
     client_vec, create_svg_from_client_tests, function, is_sorted, monitor_map, workspace_map,
 };
-use crate::handle::sort::{sort_clients, update_clients};
+use crate::handle::sort::{sort_clients, update_clients, update_clients_canvas};
 
 /// ```text
 ///                   Monitor 1                                   Monitor 2
@@ -158,16 +159,17 @@ fn ignore_monitor() {
 ///                   Monitor 1                                   Monitor 2
 ///       Workspace 1           Workspace 2           Workspace 3           Workspace 4
 /// 1  +------+  +------+ | +------+  +------+  |  +------+  +------+ | +------+  +------+
-/// 2  |  1   |  |  2   | | |  3   |  |  4   |  |  |  6   |  |  7   | | |  8   |  |  9   |
+/// 2  |  1   |  |  2   | | |  3   |  |  4   |  |  |  5   |  |  6   | | |  7   |  |  8   |
 /// 3  |      |  |      | | |      |  +------+  |  |      |  |      | | |      |  +------+
 /// 4  +------+  +------+ | +------+  +------+  |  +------+  +------+ | +------+  +------+
-/// 5  +------+  +------+ | +------+  |  5   |  |  +---------+  +---+ | +------+  |  10  |
-/// 6  |  11  |  |  12  | | |  13  |  |      |  |  |   14    |  |15 | | |  16  |  |      |
+/// 5  +------+  +------+ | +------+  |  12  |  |  +---------+  +---+ | +------+  |  16  |
+/// 6  |  9   |  |  10  | | |  11  |  |      |  |  |   13    |  |14 | | |  15  |  |      |
 /// 7  +------+  +------+ | +------+  +------+  |  +---------+  +---+ | +------+  +------+
 ///    1      2  3      4   1      2  3      4     5      6  7  8   9   5      6  7  8   9
 /// ```
+///
+/// clients are listed by workspace, the numbers are the expected order
 #[test]
-#[should_panic]
 fn ignore_monitor_ignore_workspace() {
     let monitor_data = monitor_map![(0, 0, 4, 7), (5, 0, 5, 7),];
     let workspace_data = workspace_map![(0, 0, 0), (5, 0, 0), (0, 0, 1), (5, 0, 1),];
@@ -193,15 +195,70 @@ fn ignore_monitor_ignore_workspace() {
     let update = Instant::now();
 
     let clients = update_clients(clients, Some(&workspace_data), Some(&monitor_data));
+    let clients = update_clients_canvas(clients, &workspace_data, &monitor_data, false);
     println!("updated clients: {clients:?} ({:?})", update.elapsed());
 
     let start = Instant::now();
     let clients = sort_clients(clients, true, true);
     println!("{clients:?} ({:?})", start.elapsed());
 
-    let clients = update_clients(clients, None, Some(&monitor_data));
+    let clients = update_clients_canvas(clients, &workspace_data, &monitor_data, true);
     create_svg_from_client_tests(&clients, function!(), monitor_data);
 
+    assert_eq!(clients.len(), len);
+    let order = clients
+        .iter()
+        .map(|(address, _)| address.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        order,
+        vec![
+            "0x1", "0x2", "0x5", "0x6", "0x9", "0x10", "0x13", "0x14", "0x3", "0x4", "0x8", "0x7",
+            "0x11", "0x12", "0x16", "0x15",
+        ]
+    );
+}
+
+/// two 4K monitors with 10 workspaces each, the canvas is wider than `i16::MAX`
+#[test]
+fn ignore_monitor_ignore_workspace_wide() {
+    let monitor_data = monitor_map![(0, 0, 3840, 2160), (3840, 0, 3840, 2160),];
+    let workspace_data = (0..20)
+        .map(|id| {
+            let mut workspace =
+                workspace_map![((id % 10) * 3840, 0, (id / 10) as MonitorId),].remove(0);
+            workspace.0 = id;
+            workspace
+        })
+        .collect::<Vec<_>>();
+    // one client on every workspace
+    let clients = (0..20)
+        .map(|id| {
+            let mut client = client_vec![(
+                (id / 10) * 3840 + 100,
+                100,
+                1000,
+                1000,
+                id,
+                (id / 10) as MonitorId
+            ),]
+            .remove(0);
+            client.0 = Address::new(id + 1);
+            client
+        })
+        .collect::<Vec<_>>();
+    let len = clients.len();
+
+    let clients = update_clients(clients, Some(&workspace_data), Some(&monitor_data));
+    let clients = update_clients_canvas(clients, &workspace_data, &monitor_data, false);
+    assert_eq!(clients.last().map(|(_, c)| c.x), Some(19 * 3840 + 100));
+
+    let clients = sort_clients(clients, true, true);
+    let clients = update_clients_canvas(clients, &workspace_data, &monitor_data, true);
+
     assert_eq!(clients.len(), len);
     assert!(is_sorted(&clients));
+    for (_, client) in &clients {
+        assert_eq!(client.x, (client.workspace % 10) * 3840 + 100);
+    }
 }
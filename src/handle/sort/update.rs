@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use hyprland::shared::{Address, MonitorId, WorkspaceId};
 use tracing::error;
 
@@ -40,8 +42,8 @@ pub fn update_clients(
             };
 
             if let (Some((ws_x, ws_y)), Some((md_x, md_y))) = (ws, md) {
-                c.x += (ws_x - md_x) as i16; // move x cord by workspace offset
-                c.y += (ws_y - md_y) as i16; // move y cord by workspace offset
+                c.x += ws_x - md_x; // move x cord by workspace offset
+                c.y += ws_y - md_y; // move y cord by workspace offset
                 Some((a, c))
             } else {
                 None
@@ -49,3 +51,52 @@ pub fn update_clients(
         })
         .collect()
 }
+
+/// moves clients into one contiguous canvas (used if ignore_workspaces and ignore_monitors)
+/// * 'clients' - Vector of clients to update (already moved by [`update_clients`] with workspace and monitor data)
+/// * 'workspace_data' - HashMap of workspace data
+/// * 'monitor_data' - HashMap of monitor data
+/// * 'reverse' - remove the canvas offset again (else gui will be offset)
+///
+/// places the workspaces of every monitor (sorted by monitor id) next to the workspaces of the previous monitor
+/// (client on monitor 2 will be moved right by the combined width of all workspaces on monitor 1)
+pub fn update_clients_canvas(
+    clients: Vec<(Address, ClientData)>,
+    workspace_data: &[(WorkspaceId, WorkspaceData)],
+    monitor_data: &[(MonitorId, MonitorData)],
+    reverse: bool,
+) -> Vec<(Address, ClientData)> {
+    let mut monitors = monitor_data.iter().collect::<Vec<_>>();
+    monitors.sort_by_key(|(id, _)| *id);
+
+    // monitor -> x offset on canvas
+    let mut offsets: BTreeMap<MonitorId, i32> = BTreeMap::new();
+    let mut canvas_x: i32 = 0;
+    for (id, monitor) in monitors {
+        offsets.insert(*id, canvas_x);
+        canvas_x += workspace_data
+            .iter()
+            .filter(|(_, ws)| ws.monitor == *id)
+            .map(|(_, ws)| ws.x + monitor.width as i32)
+            .max()
+            .unwrap_or(0);
+    }
+
+    clients
+        .into_iter()
+        .filter_map(|(a, mut c)| match offsets.get(&c.monitor) {
+            Some(offset) => {
+                if reverse {
+                    c.x -= *offset; // move x cord back to monitor
+                } else {
+                    c.x += *offset; // move x cord by canvas offset
+                }
+                Some((a, c))
+            }
+            None => {
+                error!("Monitor {:?} not found: {:?}", c.monitor, c);
+                None
+            }
+        })
+        .collect()
+}
@@ -35,6 +35,10 @@ pub mod global {
 
     /// global variable to store if gui is open
     pub static OPEN: std::sync::OnceLock<std::sync::Mutex<bool>> = std::sync::OnceLock::new();
+
+    /// global variable to store if toasts are disabled by the settings of the daemon (else the env variable is used)
+    pub static DISABLE_TOASTS: std::sync::OnceLock<std::sync::Mutex<bool>> =
+        std::sync::OnceLock::new();
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,22 +72,197 @@ pub enum TransferType {
     VersionCheck,
     // check if the daemon is active (gui is open)
     Open,
+    // get the current state of the daemon
+    Query,
+    // keep the stream open and receive an [`Event`] line for every change (json only)
+    Subscribe,
+    // reload the config file the daemon was started with
+    Reload,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transfer {
     pub transfer: TransferType,
+    // optional for json clients (version check is only needed for bincode)
+    #[serde(default = "current_version")]
     pub version: String,
 }
 
+fn current_version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ResponseType {
+    // switched to next/prev workspace/monitor/client or next selection in launcher
+    Dispatch,
+    // gui opened
+    Init,
+    // gui closed (and switched if not killed)
+    Close,
+    // version of the daemon
+    VersionCheck(String),
+    // if the daemon is active (gui is open)
+    Open(bool),
+    // current state of the daemon
+    Query(Box<QueryData>),
+    // stream stays open, events follow as json lines
+    Subscribe,
+    // config reloaded and gui restarted
+    Reload,
+}
+
+/// events sent to subscribed clients as json lines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Event {
+    // gui opened
+    Opened {
+        selected: Option<Active>,
+    },
+    // selected client/workspace/monitor or launcher entry changed
+    SelectionChanged {
+        selected: Option<Active>,
+        launcher_selected: Option<usize>,
+    },
+    // gui closed, selected is None if closed without switching (kill or launch)
+    Closed {
+        selected: Option<Active>,
+    },
+    // program started from the launcher
+    Launched {
+        name: String,
+        exec: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ResponseError {
+    // gui is not open (dispatch or close without init)
+    NotOpen,
+    // gui is already open (init while open)
+    AlreadyOpen,
+    // major or minor version of client and daemon don't match
+    VersionMismatch { daemon: String, client: String },
+    // no next client/workspace/monitor found (e.g. everything got filtered)
+    NoNext(SwitchType),
+    // hyprland returned an error while executing a dispatch
+    HyprlandDispatch(String),
+    // any other error
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub response: Result<ResponseType, ResponseError>,
+    pub version: String,
+}
+
+/// state of the daemon returned by [`TransferType::Query`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryData {
+    pub open: bool,
+    pub simple_config: SimpleConfig,
+    pub gui_config: GuiConfig,
+    pub selected: Option<Active>,
+    // enabled clients / workspaces / monitors in switch order
+    pub clients: Vec<QueryClient>,
+    pub workspaces: Vec<QueryWorkspace>,
+    pub monitors: Vec<QueryMonitor>,
+    pub launcher: QueryLauncher,
+}
+
+/// offset is the `--offset` a dispatch needs to select this item (only set for the current switch type)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryClient {
+    pub address: Address,
+    pub class: String,
+    pub title: String,
+    pub workspace: WorkspaceId,
+    pub monitor: MonitorId,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryWorkspace {
+    pub id: WorkspaceId,
+    pub name: String,
+    pub monitor: MonitorId,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryMonitor {
+    pub id: MonitorId,
+    pub connector: String,
+    pub offset: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryLauncher {
+    pub selected: Option<usize>,
+    // (name, exec) of the matching programs
+    pub matches: Vec<(String, String)>,
+}
+
+impl ResponseError {
+    /// exit code of the cli if the daemon returned this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ResponseError::Failed(_) => 1,
+            ResponseError::NotOpen => 2,
+            ResponseError::AlreadyOpen => 3,
+            ResponseError::VersionMismatch { .. } => 4,
+            ResponseError::NoNext(_) => 5,
+            ResponseError::HyprlandDispatch(_) => 6,
+        }
+    }
+}
+
+impl Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResponseError::NotOpen => write!(
+                f,
+                "GUI is not open (open it with `hyprswitch gui ...` before dispatching or closing)"
+            ),
+            ResponseError::AlreadyOpen => write!(
+                f,
+                "GUI is already open (close it with `hyprswitch close` or press escape)"
+            ),
+            ResponseError::VersionMismatch { daemon, client } => write!(
+                f,
+                "Client version {client} and daemon version {daemon} not matching (restart the daemon with `pkill hyprswitch && hyprswitch init &`)"
+            ),
+            ResponseError::NoNext(switch_type) => write!(
+                f,
+                "No next {} found (check the filters passed to `hyprswitch gui`)",
+                match switch_type {
+                    SwitchType::Client => "client",
+                    SwitchType::Workspace => "workspace",
+                    SwitchType::Monitor => "monitor",
+                }
+            ),
+            ResponseError::HyprlandDispatch(e) => write!(f, "Hyprland dispatch failed: {e}"),
+            ResponseError::Failed(e) => write!(f, "Command failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ResponseError {}
+
 #[derive(Debug)]
 pub struct Exec {
+    // desktop file ID, used as key in the launch history
+    pub id: Box<str>,
+    pub name: Box<str>,
     pub exec: Box<str>,
+    // program and arguments, exec is run with a shell if None
+    pub command: Option<Vec<Box<str>>>,
     pub path: Option<Box<str>>,
     pub terminal: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Active {
     Workspace(WorkspaceId),
     Monitor(MonitorId),
@@ -98,6 +277,7 @@ pub struct SharedData {
     pub active: Option<Active>,
     pub hypr_data: HyprlandData,
     pub launcher_config: LauncherConfig,
+    pub init_config: InitConfig,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -208,8 +388,20 @@ impl<A, E: Display> Warn<A> for Result<A, E> {
     }
 }
 
+/// updated by the daemon when the settings are loaded or reloaded
+pub fn set_disable_toasts(disable: bool) {
+    *global::DISABLE_TOASTS
+        .get_or_init(|| std::sync::Mutex::new(disable))
+        .lock()
+        .expect("Failed to lock DISABLE_TOASTS") = disable;
+}
+
 pub fn toast(_body: &str) {
-    if !*envs::DISABLE_TOASTS {
+    let disabled = global::DISABLE_TOASTS.get().map_or_else(
+        || envs::get_envs().overrides.disable_toasts.unwrap_or(false),
+        |disable| *disable.lock().expect("Failed to lock DISABLE_TOASTS"),
+    );
+    if !disabled {
         #[cfg(not(debug_assertions))]
         let _ = notify_rust::Notification::new()
             .summary(&format!(
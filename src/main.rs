@@ -1,10 +1,10 @@
 use anyhow::Context;
 use clap::Parser;
-use hyprswitch::daemon::gui::{debug_desktop_files, debug_list, debug_search_class};
-use hyprswitch::envs::{envvar_dump, LOG_MODULE_PATH};
+use hyprswitch::daemon::gui::{debug_desktop_files, debug_history, debug_list, debug_search_class};
+use hyprswitch::envs::{envvar_dump, get_envs, init_envs};
 use hyprswitch::{
     check_version, client, global, handle, toast, DispatchConfig, GuiConfig, InitConfig,
-    SimpleConfig, SubmapConfig, SwitchType, Warn,
+    ResponseError, SimpleConfig, SubmapConfig, SwitchType, Warn,
 };
 use std::process::exit;
 use std::sync::Mutex;
@@ -24,6 +24,9 @@ fn main() -> anyhow::Result<()> {
             exit(1);
         });
 
+    // invalid env variables are reported after logging is initialized
+    let envs_result = init_envs();
+
     let filter = EnvFilter::from_default_env().add_directive(
         if cli.global_opts.quiet {
             LevelFilter::OFF
@@ -38,11 +41,15 @@ fn main() -> anyhow::Result<()> {
     );
     let subscriber = tracing_subscriber::fmt()
         .with_timer(tracing_subscriber::fmt::time::uptime())
-        .with_target(*LOG_MODULE_PATH)
+        .with_target(get_envs().log_module_path)
         .with_env_filter(filter)
         .finish();
     tracing::subscriber::set_global_default(subscriber).warn("Unable to initialize logging");
 
+    if let Err(e) = envs_result {
+        toast(&format!("{e:#}"));
+        return Err(e.context("Failed to read env variables"));
+    }
     envvar_dump();
 
     check_version().warn("Unable to check Hyprland version, continuing anyway");
@@ -56,18 +63,57 @@ fn main() -> anyhow::Result<()> {
 
     match cli.command {
         #[cfg(feature = "config")]
-        cli::Command::Generate { exe, .. } => {
+        cli::Command::Generate { exe, config_file } => {
             info!("Loading config");
-            let config = hyprswitch::config::load().context("Failed to load config")?;
+            let config = hyprswitch::config::load(config_file.as_deref())
+                .context("Failed to load config")?;
             hyprswitch::config::validate(&config).context("Failed to validate config")?;
             let list = hyprswitch::config::create_binds_and_submaps(exe, config)
                 .context("Failed to create binds and submaps")?;
             let text = hyprswitch::config::export(list);
             println!("{}", text);
         }
+        #[cfg(feature = "config")]
+        cli::Command::Config { command } => match command {
+            cli::ConfigCommand::Init { config_file, force } => {
+                let path = config_file
+                    .or_else(hyprswitch::config::get_path)
+                    .context("Failed to get config path")?;
+                if path.exists() && !force {
+                    anyhow::bail!(
+                        "Config file {path:?} already exists, use --force to overwrite it"
+                    );
+                }
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create config dir {parent:?}"))?;
+                }
+                let config = hyprswitch::config::default_config()
+                    .context("Failed to create default config")?;
+                std::fs::write(&path, config)
+                    .with_context(|| format!("Failed to write config to {path:?}"))?;
+                println!("Created config at {}", path.display());
+            }
+            cli::ConfigCommand::Check { config_file } => {
+                let config = hyprswitch::config::load(config_file.as_deref())
+                    .context("Failed to load config")?;
+                hyprswitch::config::validate(&config).context("Failed to validate config")?;
+                println!("Config is valid ({} binds)", config.binds.len());
+            }
+            cli::ConfigCommand::PrintDefault => {
+                let config = hyprswitch::config::default_config()
+                    .context("Failed to create default config")?;
+                println!("{}", config);
+            }
+            cli::ConfigCommand::Migrate { line } => {
+                let bind = cli::migrate(&line).context("Failed to migrate bind")?;
+                println!("{}", bind);
+            }
+        },
         cli::Command::Init { init_opts } => {
             info!("Starting daemon");
-            let init_config = InitConfig::from(init_opts);
+            let init_config =
+                InitConfig::try_from(init_opts).context("Failed to read the settings")?;
             hyprswitch::daemon::start_daemon(init_config)
                 .context("Failed to run daemon")
                 .inspect_err(|_| {
@@ -82,16 +128,22 @@ fn main() -> anyhow::Result<()> {
                 warn!("Daemon not running");
                 return Ok(());
             }
-            client::send_close_daemon(kill).context("Failed to send kill command to daemon")?;
+            client::send_close_daemon(kill)
+                .map_err(exit_on_response_error)
+                .context("Failed to send kill command to daemon")?;
         }
         cli::Command::Dispatch { dispatch_config } => {
             // client::send_version_check_command()
             //     .context("Failed to send check command to daemon")?;
 
             let dispatch_config = DispatchConfig::from(dispatch_config);
-            client::send_dispatch_command(dispatch_config.clone()).with_context(|| {
-                format!("Failed to send switch command with command {dispatch_config:?} to daemon")
-            })?;
+            client::send_dispatch_command(dispatch_config.clone())
+                .map_err(exit_on_response_error)
+                .with_context(|| {
+                    format!(
+                        "Failed to send switch command with command {dispatch_config:?} to daemon"
+                    )
+                })?;
         }
         cli::Command::Simple {
             dispatch_config,
@@ -119,18 +171,48 @@ fn main() -> anyhow::Result<()> {
             simple_config,
             reverse_key,
         } => {
+            let submap_config = submap_conf.into_submap_conf(reverse_key);
+            open_gui(simple_config.into(), gui_conf.into(), submap_config)?;
+        }
+        cli::Command::GuiNoSubmap {
+            submap_info,
+            reverse_key,
+            gui_conf,
+            simple_config,
+        } => {
+            let submap_config = submap_info.into_submap_info(reverse_key);
+            open_gui(simple_config.into(), gui_conf.into(), submap_config)?;
+        }
+        cli::Command::Query => {
             if !client::daemon_running() {
-                toast("Daemon not running (add ``exec-once = hyprswitch init &`` to your Hyprland config or run ``hyprswitch init &`` it in a terminal)\nvisit https://github.com/H3rmt/hyprswitch/wiki/Examples to see Example configs");
                 return Err(anyhow::anyhow!("Daemon not running"));
             }
-            // client::send_version_check_command()
-            //     .context("Failed to send check command to daemon")?;
-
-            let config = SimpleConfig::from(simple_config);
-            let gui_config = GuiConfig::from(gui_conf);
-            let submap_config = submap_conf.into_submap_conf(reverse_key.clone());
-            client::send_init_command(config.clone(), gui_config.clone(), submap_config.clone())
-                .with_context(|| format!("Failed to send init command with config {config:?} and gui_config {gui_config:?} and submap_config {submap_config:?} to daemon"))?;
+            let data = client::send_query_command()
+                .map_err(exit_on_response_error)
+                .context("Failed to send query command to daemon")?;
+            let text = serde_json::to_string_pretty(&data).context("Failed to serialize query")?;
+            println!("{}", text);
+        }
+        cli::Command::Subscribe => {
+            if !client::daemon_running() {
+                return Err(anyhow::anyhow!("Daemon not running"));
+            }
+            client::send_subscribe_command(|event| {
+                match serde_json::to_string(&event) {
+                    Ok(text) => println!("{}", text),
+                    Err(e) => warn!("Failed to serialize event {event:?}: {e}"),
+                };
+            })
+            .map_err(exit_on_response_error)
+            .context("Failed to subscribe to daemon")?;
+        }
+        cli::Command::Reload => {
+            if !client::daemon_running() {
+                return Err(anyhow::anyhow!("Daemon not running"));
+            }
+            client::send_reload_command()
+                .map_err(exit_on_response_error)
+                .context("Failed to send reload command to daemon")?;
         }
         cli::Command::Debug { command } => {
             println!("use with -vv ... to see full logs!");
@@ -144,8 +226,39 @@ fn main() -> anyhow::Result<()> {
                 cli::DebugCommand::DesktopFiles => {
                     debug_desktop_files().warn("Failed to run debug_desktop_files");
                 }
+                cli::DebugCommand::History => {
+                    debug_history().warn("Failed to run debug_history");
+                }
             };
         }
     }
     Ok(())
 }
+
+fn open_gui(
+    config: SimpleConfig,
+    gui_config: GuiConfig,
+    submap_config: SubmapConfig,
+) -> anyhow::Result<()> {
+    if !client::daemon_running() {
+        toast("Daemon not running (add ``exec-once = hyprswitch init &`` to your Hyprland config or run ``hyprswitch init &`` it in a terminal)\nvisit https://github.com/H3rmt/hyprswitch/wiki/Examples to see Example configs");
+        return Err(anyhow::anyhow!("Daemon not running"));
+    }
+    // client::send_version_check_command()
+    //     .context("Failed to send check command to daemon")?;
+
+    client::send_init_command(config.clone(), gui_config.clone(), submap_config.clone())
+        .map_err(exit_on_response_error)
+        .with_context(|| format!("Failed to send init command with config {config:?} and gui_config {gui_config:?} and submap_config {submap_config:?} to daemon"))?;
+    Ok(())
+}
+
+/// print the error returned by the daemon and exit with its exit code (see [`ResponseError::exit_code`])
+fn exit_on_response_error(err: anyhow::Error) -> anyhow::Error {
+    if let Some(response_error) = err.downcast_ref::<ResponseError>() {
+        warn!("{err:?}");
+        eprintln!("{response_error}");
+        exit(response_error.exit_code());
+    }
+    err
+}